@@ -1,5 +1,8 @@
+use std::env;
+use std::path::PathBuf;
 use std::process::exit;
 use crate::exam::Exam;
+use crate::settings::{Command, Settings};
 
 const LOGO: &str = "
 
@@ -12,26 +15,308 @@ const LOGO: &str = "
 
 ";
 
+/// One-line alternative to the `LOGO`, for terminals too narrow to display it
+const BANNER: &str = "=== TERM PREP PLUS ===";
+
 mod exam {
-    use std::collections::HashSet;
+    use std::borrow::Cow;
+    use std::cell::{Cell, RefCell};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
     use std::{env, fs};
     use std::cmp::min;
     use std::fs::File;
     use std::hash::{Hash, Hasher};
-    use std::io::{BufReader, ErrorKind, stdin, stdout, Write};
-    use std::path::PathBuf;
+    use std::io::{self, BufReader, ErrorKind, IsTerminal, Read, stdin, stdout, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+    use chrono::Local;
+    use indexmap::IndexSet;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use flate2::Compression;
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use regex::Regex;
     use serde::{Serialize, Deserialize};
+    use serde::de::DeserializeOwned;
+    use serde_json::error::Category;
+    use terminal_size::{terminal_size, Width};
+    use crate::{attempts, bookmarks, formula, gift, history, math, menu, notes, practice_sets, recent, reports};
+    use crate::history::{QuestionResult, SessionMode};
+    use crate::menu::Selection;
+    use crate::practice_sets::PracticeSet;
+    use crate::settings::{Reveal, Settings, Strategy};
+
+    /// Like `println!`, except that the line is also written to the session transcript (if any).
+    macro_rules! outln {
+        () => { outln!("") };
+        ($($arg:tt)*) => {{
+            let line = format!($($arg)*);
+            println!("{}", line);
+            transcribe(&line);
+        }};
+    }
+
+    /// Like `eprintln!`, except that the line is also written to the session transcript (if any).
+    macro_rules! errln {
+        ($($arg:tt)*) => {{
+            let line = format!($($arg)*);
+            eprintln!("{}", line);
+            transcribe(&line);
+        }};
+    }
 
     /// The default directory for storing JSON-formatted exam files
-    const ASSETS_DIR: &str = "assets";
-
-    /// Color codes for changing the color of stdout
-    const RED_COLOR_CODE: &str = "\x1b[31m";
-    const BLUE_COLOR_CODE: &str = "\x1b[34m";
-    const GREEN_COLOR_CODE: &str = "\x1b[32m";
-    const YELLOW_COLOR_CODE: &str = "\x1b[33m";
-    const CYAN_COLOR_CODE: &str = "\x1b[36m";
-    const RESET_COLOR_CODE: &str = "\x1b[0m";
+    pub const ASSETS_DIR: &str = "assets";
+
+    /// Defaults used by the exam simulator when the exam doesn't provide `simulation` metadata
+    const DEFAULT_TIME_LIMIT_MINUTES: u64 = 90;
+    const DEFAULT_PASS_PERCENT: f64 = 70.0;
+
+    /// Files within the `assets` directory that store program data rather than exams
+    const DATA_FILES: [&str; 5] = [
+        history::HISTORY_FILE, bookmarks::BOOKMARKS_FILE, reports::REPORTS_FILE, practice_sets::PRACTICE_SETS_FILE, recent::RECENT_FILE,
+    ];
+
+    /// Location of a program data file within the `assets` directory of the current working
+    /// directory.
+    pub fn data_path(file_name: &str) -> io::Result<PathBuf> {
+        Ok(env::current_dir()?.join(ASSETS_DIR).join(file_name))
+    }
+
+    /// Loads a JSON program data file from the `assets` directory; a missing file means that
+    /// nothing has been saved yet. A file that can't be parsed (e.g., after a partial write or a
+    /// manual edit) is moved aside to `<file>.bak` with a warning, and empty data is returned so
+    /// that the program can keep running.
+    pub fn load_data<T: DeserializeOwned + Default>(file_name: &str) -> io::Result<T> {
+        let path = data_path(file_name)?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(T::default()),
+            Err(e) => return Err(e),
+        };
+        match serde_json::from_str(&contents) {
+            Ok(data) => Ok(data),
+            Err(e) => {
+                let backup = path.with_file_name(format!("{}.bak", file_name));
+                fs::rename(&path, &backup)?;
+                errln!("{}{} is corrupt ({}); it was moved to {} and will be started over{}",
+                    color(Role::Warning), path.display(), e, backup.display(), color(Role::Reset));
+                Ok(T::default())
+            },
+        }
+    }
+
+    /// Defaults used when automatically bookmarking questions: a question is bookmarked once it
+    /// has been missed more than this many times (across sessions), or answered more slowly
+    const DEFAULT_FLAG_MISSES: usize = 2;
+    const DEFAULT_FLAG_SECONDS: u64 = 60;
+
+    /// Largest difference between a numeric answer and an accepted answer that is still correct;
+    /// accepts answers rounded to 2 decimal places
+    const NUMERIC_TOLERANCE: f64 = 0.005 + 1e-9;
+
+    /// Number of times in a row a question must be answered correctly before `--skip-mastered`
+    /// leaves it out
+    const DEFAULT_MASTERY_STREAK: usize = 3;
+
+    /// Number of questions studied by the quick quiz when the exam doesn't have a `default_count`
+    const QUICK_QUIZ_COUNT: usize = 10;
+
+    /// Number of times a question must have been missed (across sessions) before its explanation
+    /// is displayed in a box to draw attention to it
+    const PROMINENT_EXPLANATION_MISSES: usize = 2;
+
+    /// Words shorter than this aren't considered key terms of an explanation
+    const MIN_KEY_TERM_LEN: usize = 4;
+
+    /// Common words that are never considered key terms of an explanation
+    const STOP_WORDS: [&str; 24] = [
+        "about", "also", "because", "been", "being", "both", "each", "from", "have", "into", "more",
+        "most", "only", "other", "such", "than", "that", "their", "then", "there", "these", "this",
+        "when", "which",
+    ];
+
+    /// Layout of choices displayed in columns: the most columns used, the fewest choices worth
+    /// splitting into columns, and the spaces between columns
+    const MAX_CHOICE_COLUMNS: usize = 3;
+    const MIN_COLUMN_CHOICES: usize = 4;
+    const CHOICE_COLUMN_GAP: usize = 4;
+
+    /// Number of invalid selections in a row after which a multiple select question is skipped
+    const MAX_INVALID_SELECTIONS: usize = 5;
+
+    /// Number of questions that can share the same distractor before `validate` warns about it
+    const DISTRACTOR_REUSE_LIMIT: usize = 3;
+
+    /// How the correct answers are revealed after an incorrect answer; `{answers}` is replaced
+    /// with the comma-separated answers
+    const DEFAULT_REVEAL_TEMPLATE: &str = "The correct answer(s): {answers}";
+
+    /// Fraction of the smaller exam's questions that two exam files in the same directory must
+    /// share before they are considered duplicates
+    const DUPLICATE_OVERLAP: f64 = 0.8;
+
+    /// Prefix of an explanation that is read from a file (relative to the exam file) instead
+    const EXPLANATION_FILE_PREFIX: &str = "@file:";
+
+    /// Choices that are always displayed last since they refer to the other choices
+    const POSITIONAL_CHOICES: [&str; 2] = ["all of the above", "none of the above"];
+
+    /// File name suffix of gzip-compressed exam files
+    const GZIP_SUFFIX: &str = ".json.gz";
+
+    /// File name suffix of exams in Moodle's GIFT quiz format, which are imported when loaded
+    const GIFT_SUFFIX: &str = ".gift";
+
+    /// The largest exam file (in bytes) that will be downloaded from a remote URL
+    const MAX_DOWNLOAD_BYTES: u64 = 10 * 1024 * 1024;
+
+    /// The file that the session's output is copied to, when a transcript was requested
+    static TRANSCRIPT: OnceLock<(PathBuf, Mutex<File>)> = OnceLock::new();
+
+    /// Starts copying the session's output (and the user's answers) to a new, timestamped
+    /// transcript file in the current directory.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<PathBuf>` - the path of the transcript file.
+    pub fn start_transcript() -> io::Result<PathBuf> {
+        let path = env::current_dir()?.join(format!("transcript-{}.txt", Local::now().format("%Y%m%d-%H%M%S")));
+        let file = File::create(&path)?;
+        let _ = TRANSCRIPT.set((path.clone(), Mutex::new(file)));
+        Ok(path)
+    }
+
+    /// Path of the transcript file, if a transcript was started.
+    pub fn transcript_path() -> Option<&'static Path> {
+        TRANSCRIPT.get().map(|(path, _)| path.as_path())
+    }
+
+    /// Helper function that writes a line to the transcript (if any) without its color codes;
+    /// failing to write the transcript shouldn't interrupt the session, so errors are ignored.
+    fn transcribe(line: &str) {
+        if let Some((_, file)) = TRANSCRIPT.get() {
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "{}", strip_escape_codes(line));
+            }
+        }
+    }
+
+    /// Helper function that removes the ANSI escape codes (e.g., colors) from a line of output.
+    fn strip_escape_codes(line: &str) -> String {
+        let mut stripped = String::with_capacity(line.len());
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                // Escape codes end with a letter, e.g., `\x1b[1;32m`
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            } else {
+                stripped.push(c);
+            }
+        }
+        stripped
+    }
+
+    /// Determines whether stdout is an interactive terminal; when it isn't (e.g., output is
+    /// redirected to a file or another program), decorative output is suppressed.
+    pub fn stdout_is_terminal() -> bool {
+        static IS_TERMINAL: OnceLock<bool> = OnceLock::new();
+        *IS_TERMINAL.get_or_init(|| stdout().is_terminal())
+    }
+
+    /// Determines whether color codes should be written; follows the `NO_COLOR` convention
+    /// (https://no-color.org) where any value disables color, and never writes color codes when
+    /// stdout isn't a terminal.
+    fn colors_enabled() -> bool {
+        env::var_os("NO_COLOR").is_none() && stdout_is_terminal()
+    }
+
+    /// Helper function that all escape codes are routed through; returns the given escape code
+    /// when colors are enabled, otherwise an empty string.
+    fn style(code: &'static str) -> &'static str {
+        if colors_enabled() { code } else { "" }
+    }
+
+    /// Helper function that returns the escape code of the active theme for the given role, or an
+    /// empty string when colors are disabled.
+    pub fn color(role: Role) -> &'static str {
+        style(THEME.get().copied().unwrap_or_default().code(role))
+    }
+
+    /// The theme selected for this run; the default theme is used until one is set
+    static THEME: OnceLock<Theme> = OnceLock::new();
+
+    /// Selects the color theme used for all colored output; only the first call has an effect.
+    pub fn set_theme(theme: Theme) {
+        let _ = THEME.set(theme);
+    }
+
+    /// Named color palettes; each maps the roles of colored output to ANSI escape codes
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum Theme {
+        /// The original palette, intended for terminals with a dark background
+        #[default]
+        Dark,
+        /// Avoids yellow and cyan, which are hard to read on a light background
+        Light,
+        /// Bold, bright colors
+        HighContrast,
+        /// No colors; only bold text for correct/incorrect answers and errors
+        Mono,
+    }
+
+    /// The roles of colored output, which the active `Theme` assigns colors to
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Role {
+        /// Correct answers and successful operations
+        Success,
+        /// Incorrect answers and errors
+        Error,
+        /// Warnings that don't stop the program
+        Warning,
+        /// Explanations and revealed answers
+        Explanation,
+        /// Question headers (e.g., the question number and remaining time)
+        Prompt,
+        /// Choices and hints
+        Hint,
+        /// Informational messages
+        Info,
+        /// References
+        Reference,
+        /// Restores the terminal's default colors
+        Reset,
+    }
+
+    impl Theme {
+        /// Escape code used for the given role in this theme.
+        fn code(self, role: Role) -> &'static str {
+            match (self, role) {
+                (_, Role::Reset) => "\x1b[0m",
+                (Theme::Dark, Role::Success) => "\x1b[32m",
+                (Theme::Dark, Role::Error) => "\x1b[31m",
+                (Theme::Dark, Role::Warning | Role::Explanation) => "\x1b[33m",
+                (Theme::Dark, Role::Prompt | Role::Reference) => "\x1b[36m",
+                (Theme::Dark, Role::Hint | Role::Info) => "\x1b[34m",
+                (Theme::Light, Role::Success) => "\x1b[32m",
+                (Theme::Light, Role::Error) => "\x1b[31m",
+                (Theme::Light, Role::Warning | Role::Explanation | Role::Reference) => "\x1b[35m",
+                (Theme::Light, Role::Prompt | Role::Hint | Role::Info) => "\x1b[34m",
+                (Theme::HighContrast, Role::Success) => "\x1b[1;92m",
+                (Theme::HighContrast, Role::Error) => "\x1b[1;91m",
+                (Theme::HighContrast, Role::Warning | Role::Explanation) => "\x1b[1;93m",
+                (Theme::HighContrast, Role::Prompt | Role::Reference) => "\x1b[1;96m",
+                (Theme::HighContrast, Role::Hint | Role::Info) => "\x1b[1;95m",
+                (Theme::Mono, Role::Success | Role::Error) => "\x1b[1m",
+                (Theme::Mono, _) => "",
+            }
+        }
+    }
+
+    /// Escape codes for italicizing text
     const START_ITALICS: &str = "\x1B[3m";
     const END_ITALICS: &str = "\x1B[23m";
 
@@ -39,402 +324,6044 @@ mod exam {
     #[derive(Debug, Deserialize, Serialize)]
     pub struct Exam {
         name: String,
-        questions: HashSet<Question>,
+        /// The questions in the order they were authored (followed by those of included files)
+        questions: IndexSet<Question>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        simulation: Option<Simulation>,
+        /// Paths (relative to this file) of other exam files whose questions are merged in
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        include: Vec<String>,
+        /// Equivalent phrasings of answers (e.g., "HTTP" and "HyperText Transfer Protocol"),
+        /// accepted interchangeably for user entry questions
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        synonyms: HashMap<String, Vec<String>>,
+        /// Contents of the files referenced by `@file:` explanations, read when first displayed;
+        /// `None` if the file couldn't be read
+        #[serde(skip)]
+        explanation_files: RefCell<HashMap<PathBuf, Option<String>>>,
+        /// Accepted answers of `regex` questions, compiled when first graded; `None` if the pattern
+        /// isn't a valid regular expression
+        #[serde(skip)]
+        patterns: RefCell<HashMap<String, Option<Regex>>>,
+        /// The file the exam was loaded from, if any; used to reload it when playing again
+        #[serde(skip)]
+        source: Option<PathBuf>,
+        /// Number of questions studied without asking, when the exam was picked by the quick quiz
+        #[serde(skip)]
+        quick_count: Option<usize>,
+        /// Instructions displayed before the session starts, like a certification exam's
+        /// instructions screen
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        instructions: Option<String>,
+        /// Rules applied to both the user's answer and the accepted answers before comparing them,
+        /// for every user entry question of the exam
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        normalize: Vec<Normalization>,
+        /// Pools that study sessions are drawn from, each serving a number of questions of one
+        /// section; when empty, questions are drawn from the whole exam
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pools: Vec<Pool>,
+        /// Number of questions the author recommends per session; offered as the default answer
+        /// when asked how many questions to review
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default_count: Option<usize>,
+        /// Phrases shown when an answer is correct or incorrect, in place of the defaults
+        #[serde(default, skip_serializing_if = "Feedback::is_empty")]
+        feedback: Feedback,
+        /// Whether the questions are always asked in the order they were authored (e.g., for a
+        /// tutorial or walkthrough) instead of being shuffled
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        preserve_order: bool,
     }
 
-    /// The questions that comprise an Exam
+    /// Phrases that an exam shows after each answer; one is picked at random every time, so that
+    /// the encouragement varies. An empty list keeps the default phrase.
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    pub struct Feedback {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        correct: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        incorrect: Vec<String>,
+    }
+
+    impl Feedback {
+        fn is_empty(&self) -> bool {
+            self.correct.is_empty() && self.incorrect.is_empty()
+        }
+    }
+
+    /// A pool of questions that a study session draws a fixed number of questions from, like a
+    /// certification exam drawing 5 networking questions from a larger bank
     #[derive(Debug, Deserialize, Serialize)]
+    pub struct Pool {
+        /// The `section` whose questions make up the pool
+        section: String,
+        /// Number of questions drawn from the pool
+        draw: usize,
+    }
+
+    /// Optional exam metadata describing the blueprint used by the exam simulator; any field that
+    /// is omitted falls back to a sensible default.
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    pub struct Simulation {
+        /// Number of questions on the simulated exam (defaults to every question)
+        #[serde(default)]
+        question_count: Option<usize>,
+        /// Time allowed for the whole exam, in minutes
+        #[serde(default)]
+        time_limit_minutes: Option<u64>,
+        /// Minimum percentage of correct answers required to pass
+        #[serde(default)]
+        pass_percent: Option<f64>,
+    }
+
+    /// The questions that comprise an Exam; `choices` (unused by user entry questions),
+    /// `explanation`, and `refs` may be omitted, and unrecognized fields (e.g., an author's own
+    /// metadata) are ignored.
+    #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
     pub struct Question {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
         q_type: String,
         prompt: String,
-        choices: HashSet<String>,
+        /// The choices of a multiple choice or multiple select question, or the hints of a user entry
+        /// question; kept in the order they were authored, which is the order hints are revealed in
+        #[serde(default)]
+        choices: IndexSet<String>,
         answer: Vec<String>,
+        #[serde(default)]
         explanation: String,
+        #[serde(default)]
         refs: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        difficulty: Option<Difficulty>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        section: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pin_to_end: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        prompt_variants: Vec<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        confirm: bool,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        followups: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        ordered: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        match_mode: Option<MatchMode>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        reversible: bool,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        always_include: bool,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        multiline: bool,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, Param>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+        /// Directory that an `@file:` explanation is relative to: that of the file the question
+        /// was loaded from, which is no longer known once included files are merged in
+        #[serde(skip)]
+        explanation_dir: Option<PathBuf>,
     }
 
-    /// The next three are required to utilize Questions as a HashSet; this helps ensure that
-    /// the sequence of questions are not revealed in the same sequence (as would be the case if
-    /// the Exam struct utilized a Vec<Question>)
-    impl PartialEq<Self> for Question {
-        fn eq(&self, other: &Self) -> bool {
-            self.q_type == other.q_type
-            && self.prompt == other.prompt
-            && self.choices == other.choices
-            && self.answer == other.answer
-            && self.explanation == other.explanation
-            && self.refs == other.refs
-        }
+    /// The values a parameter of a templated question can take; a value is picked at random each
+    /// time the question is asked
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum Param {
+        /// A whole number between `min` and `max` (inclusive)
+        Range { min: i64, max: i64 },
+        /// One of the listed values
+        Values(Vec<f64>),
     }
-    impl Eq for Question {}
 
-    impl Hash for Question {
+    impl Hash for Param {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.q_type.hash(state);
-            self.prompt.hash(state);
-            self.choices.iter().for_each(|choice| choice.hash(state));
-            self.answer.iter().for_each(|ans| ans.hash(state));
-            self.explanation.hash(state);
-            self.refs.hash(state);
+            match self {
+                Param::Range { min, max } => (min, max).hash(state),
+                // Floating point numbers aren't `Hash`, so their bits are hashed instead
+                Param::Values(values) => values.iter().for_each(|value| value.to_bits().hash(state)),
+            }
         }
     }
 
-    impl Exam {
-        /// Attempts to create an Exam if an exam JSON file exists and is properly formatted.
-        pub fn new() -> Option<Self> {
-            match env::current_dir() {
-                Ok(cwd) if Self::create_asset_dir(&cwd) => Some(Self::get_exam(&cwd)),
-                _ => {
-                    eprintln!("{}Unable to create Exam{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                    None
-                },
+    impl Param {
+        /// Picks one of the parameter's values at random.
+        fn pick(&self, rng: &mut StdRng) -> f64 {
+            match self {
+                Param::Range { min, max } => rng.gen_range(*min..=*max) as f64,
+                Param::Values(values) => values.choose(rng).copied().unwrap_or_default(),
             }
         }
 
-        /// Helper function that ensures the creation of the default `assets` directory for storing
-        /// JSON-formatted exam files.
-        ///
-        /// # Argument
-        ///
-        /// * `cwd` - a reference to the current working directory as a `PathBuf` reference.
-        ///
-        /// # Returns
-        ///
-        /// * `bool` - If the `assets` directory already exists, or if the `assets` directory was
-        ///   created without any errors, then the program will print out the applicable message and
-        ///   return `true` - otherwise the program will print an error message to `stderr` and return
-        ///   `false`.
-        fn create_asset_dir(cwd: &PathBuf) -> bool {
-            let assets_dir = cwd.join(ASSETS_DIR);
-            match fs::create_dir(assets_dir) {
-                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
-                    println!("The {} directory already exists; no need to create it...", ASSETS_DIR);
-                    true
-                },
-                Err(e) => {
-                    eprintln!("An error {} occurred creating the {} directory...", e, ASSETS_DIR);
-                    false
-                },
-                Ok(()) => {
-                    println!("Created the {} directory", ASSETS_DIR);
-                    true
-                },
+        /// The parameter's first value, used to check that answer formulas can be evaluated.
+        fn first(&self) -> Option<f64> {
+            match self {
+                Param::Range { min, .. } => Some(*min as f64),
+                Param::Values(values) => values.first().copied(),
             }
         }
+    }
 
-        /// Gets the appropriate exam directory from the user for the study session, attempts to
-        /// get the appropriate `Exam` via an `Option` depending on whether the JSON file exists.
-        fn get_exam(cwd: &PathBuf) -> Exam {
-            let result: Exam = loop {
-                let assets_dir: PathBuf = Self::select_asset_directory(cwd);
-                match Self::display_and_collect_available_exams(assets_dir) {
-                    Some(empty_dir) if empty_dir.is_empty() => {
-                        eprintln!("{}There are no available exam files in chosen directory{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                    },
-                    Some(exam_dir) => {
-                        // Get the appropriate exam from the list provided
-                        let exam_path = loop {
-                            let prompt = "Enter the exam number (e.g., '1', '2', '3', ...): ";
-                            let index = Self::input(prompt).parse::<usize>().unwrap_or(usize::MAX) - 1;
-                            match exam_dir.get(index) {
-                                Some(exam) => break exam,
-                                _ => eprintln!("{}Please make a valid selection!{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                            }
-                        };
-                        // Open the file and attempt to parse the contents into an exam
-                        if let Ok(exam_file) = File::open(exam_path) {
-                            let reader = BufReader::new(exam_file);
-                            match serde_json::from_reader(reader) {
-                                Ok(exam) => break exam,
-                                Err(e) => eprintln!("{}Unable to parse JSON file:\t{}{}", RED_COLOR_CODE, e, RESET_COLOR_CODE),
-                            }
-                        } else {
-                            eprintln!("{}Unable to open selected exam{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                        }
-                    },
-                    None => eprintln!("{}Unable to get list of exam files in chosen directory{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                }
-            };
-            result
+    /// An answer submitted in a response file: a single answer, or a list of answers for
+    /// multiple select questions
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(untagged)]
+    enum Submission {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    /// A response file written by `--collect`: the submitted answers, along with a hash of the
+    /// exam's questions so that grading can verify that the same exam was answered
+    #[derive(Debug, Deserialize, Serialize)]
+    struct ResponseBundle {
+        exam: String,
+        exam_hash: String,
+        answers: BTreeMap<String, Submission>,
+    }
+
+    /// Contents of a response file given to `--answers`: either a bundle written by `--collect`,
+    /// or a plain object that maps question IDs to the submitted answers
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum ResponseFile {
+        Bundle(ResponseBundle),
+        Plain(HashMap<String, Submission>),
+    }
+
+    /// The user's answer to a question, and whether it was correct
+    #[derive(Debug)]
+    struct Response {
+        /// The selected choice(s), or the entered text for user entry questions
+        given: Vec<String>,
+        /// Whether the answer was correct
+        is_correct: bool,
+        /// Number of hints revealed before answering
+        hints: usize,
+    }
+
+    /// What an entry at a multiple select prompt amounts to
+    #[derive(Debug, PartialEq)]
+    enum SelectEntry<'a> {
+        /// Every entry named one of the choices
+        Chosen(Vec<&'a String>),
+        /// These entries (quoted) don't name a choice; the user can try again
+        Invalid(Vec<String>),
+        /// The user asked to skip the question
+        Skip,
+        /// Too many invalid selections were entered, so the question is skipped
+        GiveUp,
+    }
+
+    /// A condition on questions that can be combined with others in the interactive filter builder
+    #[derive(Debug)]
+    enum Filter {
+        Section(String),
+        Difficulty(Difficulty),
+        /// A reference containing the text (ignoring case)
+        Reference(String),
+        Group(String),
+        /// The IDs of the questions missed in the previous session of the exam
+        Missed(BTreeSet<String>),
+    }
+
+    impl Filter {
+        fn matches(&self, question: &Question) -> bool {
+            match self {
+                Filter::Section(section) => question.section.as_ref() == Some(section),
+                Filter::Difficulty(difficulty) => question.difficulty == Some(*difficulty),
+                Filter::Reference(reference) => question.cites(reference),
+                Filter::Group(group) => question.group.as_ref() == Some(group),
+                Filter::Missed(ids) => ids.contains(&question.id()),
+            }
         }
 
-        /// Helper function that obtains the path to the directory where the user has stored their
-        /// exam files. The user can opt to use the `assets` directory, which is created as one of
-        /// the initial steps in the `Exam` constructor, or uses a different directory of the user's
-        /// choosing.
-        fn select_asset_directory(cwd: &PathBuf) -> PathBuf {
-            loop {
-                match Self::input("\nSearch default directory for exam files (Y/n)? ").chars().next().unwrap_or('n') {
-                    'y' | 'Y' => break cwd.join(ASSETS_DIR),
-                    'n' | 'N' => {
-                        let user_dir = PathBuf::from(Self::input_confirm("Enter full path to exam directory: "));
-                        if user_dir.exists() && user_dir.is_dir() {
-                            break user_dir
-                        } else {
-                            eprintln!("{}Please enter a valid directory!{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                        }
-                    },
-                    _ => eprintln!("{}Please enter a valid option!{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                }
-            }
-        }
-
-        /// Lists the exams that are available to study by the file extension ending in `json` at
-        /// the directory provided. If the directory with the exam files exist, this display the
-        /// exams with a number prefix and return an `Option` with the vector containing the file
-        /// paths.
-        fn display_and_collect_available_exams(dir: PathBuf) -> Option<Vec<PathBuf>> {
-            if let Ok(entries) = fs::read_dir(&dir) {
-                println!("\nThe following compatible exam files were found:");
-                let exams: Vec<PathBuf> = entries
-                    .filter(|e|
-                         e.as_ref().is_ok_and(|e|
-                             e.path().is_file() && e.path().extension().map_or(false, |ext| ext == "json")
-                         )
-                    )
-                    .enumerate()
-                    .map(|(index, e)| {
-                        let path: PathBuf = e.unwrap().path();
-                        let filename: &str = path.file_name().unwrap().to_str().unwrap();
-                        println!("\t{}{}.) {}{}", BLUE_COLOR_CODE, index + 1, filename, RESET_COLOR_CODE);
-                        path
-                    })
-                    .collect();
-                Some(exams)
-            } else {
-                eprintln!("{}Unable to read files in selected directory{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                None
+        fn describe(&self) -> String {
+            match self {
+                Filter::Section(section) => format!("section is '{}'", section),
+                Filter::Difficulty(difficulty) => format!("difficulty is {:?}", difficulty),
+                Filter::Reference(reference) => format!("a reference contains '{}'", reference),
+                Filter::Group(group) => format!("group is '{}'", group),
+                Filter::Missed(ids) => format!("missed last time ({} questions)", ids.len()),
             }
         }
+    }
 
-        /// Helper function for displaying a prompt that the user can respond to in-line with the
-        /// prompt.
-        fn input(prompt: &str) -> String {
-            let mut temp: String = String::new();
-            while temp.trim().is_empty() {
-                print!("{}", prompt);
-                stdout().flush().expect("Unable to flush stdout...");
-                stdin().read_line(&mut temp).expect("Unable to read from stdin");
+    /// Filters combined with AND (every filter must match) or OR (any filter must match); no
+    /// filters match every question
+    #[derive(Debug, Default)]
+    struct FilterSet {
+        filters: Vec<Filter>,
+        /// Whether the filters are combined with OR instead of AND
+        any: bool,
+    }
+
+    impl FilterSet {
+        fn matches(&self, question: &Question) -> bool {
+            match (self.filters.is_empty(), self.any) {
+                (true, _) => true,
+                (false, true) => self.filters.iter().any(|filter| filter.matches(question)),
+                (false, false) => self.filters.iter().all(|filter| filter.matches(question)),
             }
-            temp.trim().to_string()
         }
+    }
 
-        /// Helper function that prompts the user to enter info in-line with a prompt twice to
-        /// verify the user's input is accurate.
-        fn input_confirm(prompt: &str) -> String {
-            loop {
-                let in1: String = Self::input(prompt);
-                let in2: String = Self::input("Confirm entry: ");
-                if in1.eq(&in2) {
-                    return in2;
-                } else {
-                    eprintln!("{}Entries must match!{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                }
+    /// Totals of a finished study session (including any replays), and whether the user chose to
+    /// continue with the next exam
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct StudyOutcome {
+        /// Number of questions answered correctly
+        pub correct: usize,
+        /// Number of questions answered
+        pub answered: usize,
+        /// Whether the user chose to continue with the next exam
+        pub next_exam: bool,
+    }
+
+    /// Result of validating an exam
+    #[derive(Debug, Default)]
+    pub struct Validation {
+        /// Mistakes that make questions impossible to study correctly
+        pub problems: Vec<String>,
+        /// Advisory findings that don't prevent studying but could be improved
+        pub warnings: Vec<String>,
+    }
+
+    /// In-session commands that interrupt answering the current question
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum SessionCommand {
+        /// Return to the previous question so that it can be answered again
+        Back,
+        /// End the session early; questions answered so far still count
+        Quit,
+        /// Move on to the next question without scoring the current one
+        Skip,
+    }
+
+    /// Commands recognized at any answer prompt, and what they do; listed by the `help` command
+    const SESSION_COMMANDS: [(&str, &str); 7] = [
+        ("hint", "Show the next hint of a user entry question, if it has any"),
+        ("note", "Save a note about the question to the notes file"),
+        ("broken", "Report the question as broken and skip it"),
+        ("back", "Return to the previous question to answer it again"),
+        ("skip", "Skip a confusing question without answering it (multiple select only)"),
+        ("quit", "End the session; questions answered so far still count"),
+        ("help, ?", "List these commands"),
+    ];
+
+    /// Optional difficulty tier of a question; used for filtering and for the score breakdown
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Difficulty {
+        Easy,
+        Medium,
+        Hard,
+    }
+
+    /// How the answer to a user entry question is compared against its accepted answers
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum MatchMode {
+        /// The answer must be one of the accepted answers (or their synonyms)
+        #[default]
+        Exact,
+        /// The answer must contain one of the accepted answers (or their synonyms), ignoring case
+        Contains,
+        /// The whole answer, after the exam's `normalize` rules, must match one of the accepted
+        /// answers, read as regular expressions (e.g., `colou?r`; `.*cat.*` for any mention of "cat")
+        Regex,
+        /// The answer must be a number equal to one of the accepted answers, give or take the
+        /// rounding to 2 decimal places
+        Numeric,
+        /// The answer must mention at least half of the key terms of one of the accepted answers
+        Keywords,
+    }
+
+    /// A rule that makes comparing the answers to user entry questions more lenient
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Normalization {
+        /// Ignore case
+        Lowercase,
+        /// Ignore punctuation (any character that isn't alphanumeric or whitespace)
+        Punctuation,
+        /// Ignore leading and trailing whitespace, and treat runs of whitespace as a single space
+        Whitespace,
+        /// Ignore a leading article ("the", "a", or "an")
+        Articles,
+    }
+
+    /// These two (along with the derived `PartialEq`) are required to store Questions in the
+    /// exam's `IndexSet`, which drops duplicate questions while keeping the others in the order
+    /// they were authored; sessions shuffle that order unless the exam sets `preserve_order`
+    impl Eq for Question {}
+
+    impl Hash for Question {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            // Destructured so that a new field can't be left out of the hash by accident
+            let Question {
+                id, q_type, prompt, choices, answer, explanation, refs, difficulty, section, pin_to_end, prompt_variants,
+                confirm, followups, ordered, match_mode, reversible, always_include, multiline, params, group, explanation_dir,
+            } = self;
+            id.hash(state);
+            q_type.hash(state);
+            prompt.hash(state);
+            sorted(choices).hash(state);
+            answer.iter().for_each(|ans| ans.hash(state));
+            explanation.hash(state);
+            refs.hash(state);
+            difficulty.hash(state);
+            section.hash(state);
+            pin_to_end.hash(state);
+            prompt_variants.hash(state);
+            confirm.hash(state);
+            sorted(followups).hash(state);
+            ordered.hash(state);
+            match_mode.hash(state);
+            reversible.hash(state);
+            always_include.hash(state);
+            multiline.hash(state);
+            params.hash(state);
+            group.hash(state);
+            explanation_dir.hash(state);
+        }
+    }
+
+    /// Helper function that collects a collection compared regardless of order (a `HashMap`, or
+    /// the `IndexSet` of choices) into a sorted `Vec`, so that equal questions hash the same.
+    fn sorted<T: Ord>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+        let mut items: Vec<T> = items.into_iter().collect();
+        items.sort();
+        items
+    }
+
+    impl Question {
+        /// Stable identifier for the question; uses the optional `id` field when the author
+        /// provided one, otherwise one is derived from the `q_type` and `prompt` fields so that it
+        /// stays the same between sessions.
+        pub fn id(&self) -> String {
+            match &self.id {
+                Some(id) => id.clone(),
+                None => format!("{:016x}", stable_hash(&format!("{}:{}", self.q_type, self.prompt))),
             }
         }
 
-        /// Method for studying questions from an exam in the `assets` directory. This will ask the
-        /// user how many questions they'd like to study. If the user enters a number of questions
-        /// that exceeds the number of questions in the exam JSON file, then the entire contents of
-        /// the exam file will be studied. After the study session has completed, a ratio of the
-        /// number of questions correctly answered to the number of questions studied will be
-        /// displayed.
-        ///
-        /// # Panics
-        /// if the JSON file that was chosen doesn't match one of the 3 allowable `q_type` variations
-        /// * `mc` - for multiple choice questions
-        /// * `ms` - for multiple select questions
-        /// * `ue` - for user entry
-        pub fn study(&self) {
-            // Counts the number of questions the user answers correctly
-            let mut num_correct = 0;
+        /// The question's (default) prompt.
+        pub fn prompt(&self) -> &str {
+            &self.prompt
+        }
 
-            // Display the exam the user selected to study
-            println!("\n\n{}Exam selected: {}{}", GREEN_COLOR_CODE, &self.name, RESET_COLOR_CODE);
-
-            // Ask the user for desired number of questions and save result
-            let num_questions: usize = loop {
-                match Self::input("How many questions would you like to review? ").parse::<usize>() {
-                    Ok(num) if num > 0 => break min(num, self.questions.len()),
-                    _ => eprintln!("{}Please enter a positive number!{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                }
-            };
-
-            // Iterate over the number of questions the user specified
-            for question in self.questions.iter().take(num_questions) {
-                // Display the question prompt
-                println!("\n{}", question.prompt);
-
-                // logic depends on question type
-                match question.q_type.as_ref() {
-                    "mc" => {
-                        let choices = Self::display_choices_and_collect(question);
-
-                        // Get the user's answer based on the letter prefix printed above
-                        let user_answer: String = loop {
-                            let letter_choice: String = Self::input("Enter answer (e.g., 'a', 'b', 'c', ...): ");
-                            let choice_as_index: usize = letter_choice
-                                .chars()
-                                .next()
-                                .map_or(usize::MAX, |c| (c as u8 - b'a') as usize);
-                            match choices.get(choice_as_index) {
-                                Some(choice) => break choice.to_string(),
-                                None => eprintln!("{}Please pick a valid answer!{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                            }
-                        };
+        /// Wording of the prompt to display; one of the `prompt_variants` is picked at random when
+        /// the author provided any, otherwise the `prompt` itself is used.
+        fn pick_prompt(&self, rng: &mut StdRng) -> &str {
+            self.prompt_variants.choose(rng).unwrap_or(&self.prompt)
+        }
 
-                        // Get the correct answer from the vector and print out user's result
-                        match question.answer.get(0) {
-                            Some(correct_ans) if user_answer.eq(correct_ans.as_str()) => {
-                                println!("{}Correct!{}", GREEN_COLOR_CODE, RESET_COLOR_CODE);
-                                num_correct += 1;
-                            },
-                            _ => {
-                                println!("{}Incorrect...{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                                println!("{}The correct answer(s): {:#?}{}", YELLOW_COLOR_CODE, question.answer, RESET_COLOR_CODE);
-                            },
+        /// Whether any of the question's references contains the given text (ignoring case), e.g.,
+        /// "Chapter 7".
+        fn cites(&self, reference: &str) -> bool {
+            let reference = reference.to_lowercase();
+            self.refs.iter().any(|source| source.to_lowercase().contains(&reference))
+        }
 
-                        }
-                    },
-                    "ms" => {
-                        let choices = Self::display_choices_and_collect(question);
-                        // Get the user's multiple select answer(s)
-                        let mut user_sel = loop {
-                            let mut has_bad_input = false;
-                            let prompt = "Enter comma-separated answer (e.g., 'a, b', or 'c'): ";
-                            let user_ans = Self::input(prompt).split(", ").filter_map(|choice| {
-                                match choice.chars().next().map_or(usize::MAX, |c| (c as u8 - b'a') as usize) {
-                                    num if choices.get(num).is_none() => {
-                                        eprintln!("{}Please enter a valid selection from available choices{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                                        has_bad_input = true;
-                                        None
-                                    },
-                                    num => choices.get(num),
-                                }
-                            })
-                                .collect::<HashSet<&String>>();
-                            if !has_bad_input {
-                                break user_ans
-                            }
-                        };
-                        // If # of user choices != number of answer, then it's incorrect
-                        if user_sel.len() == question.answer.len() {
-                            // Iterate over correct answers, removing each from user's choices
-                            for ans in question.answer.iter() {
-                                user_sel.remove(ans);
-                            }
-                        }
-                        // If user answered correctly, then the HashSet should've had all items removed
-                        if user_sel.is_empty() {
-                            println!("{}Correct!{}", GREEN_COLOR_CODE, RESET_COLOR_CODE);
-                            num_correct += 1;
-                        } else {
-                            println!("{}Incorrect...{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                            println!("{}The correct answer(s): {:#?}{}", YELLOW_COLOR_CODE, question.answer, RESET_COLOR_CODE);
-                        }
-                    },
-                    "ue" => {
-                        // Collect the hint(s), if any
-                        let hints = Self::display_choices_and_collect(question);
-                        // Get the user's input; display prompt and show hint(s), if available
-                        let user_ans: String = loop {
-                            match hints.len() {
-                                num if num > 0 => {
-                                    let input = Self::input("Enter your answer (or enter 'hint' to see hints): ");
-                                    if input.eq_ignore_ascii_case("hint") {
-                                        Self::display_hints(&hints);
-                                    } else {
-                                        break input
-                                    }
-                                },
-                                _ => {
-                                    let input = Self::input("Enter your answer: ");
-                                    if input.eq_ignore_ascii_case("hint") {
-                                        eprintln!("{}This question doesn't have any hints...{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                                    } else {
-                                        break input
-                                    }
-                                }
-                            }
-                        };
-                        let mut is_correct = false;
-                        for answer in question.answer.iter() {
-                            if user_ans.eq(answer) {
-                                is_correct = true;
-                                break;
-                            }
-                        }
-                        if is_correct {
-                            println!("{}Correct!{}", GREEN_COLOR_CODE, RESET_COLOR_CODE);
-                            num_correct += 1;
-                        } else {
-                            println!("{}Incorrect...{}", RED_COLOR_CODE, RESET_COLOR_CODE);
-                            println!("{}The correct answer(s): {:#?}{}", YELLOW_COLOR_CODE, question.answer, RESET_COLOR_CODE);
-                        }
-                    },
-                    _ => panic!("{}q_type field not recognized{}", RED_COLOR_CODE, RESET_COLOR_CODE),
-                }
-                // Sleep for a bit so that the user can see the result before adding extra text
-                std::thread::sleep(std::time::Duration::from_millis(500));
+        /// The reverse of a reversible question, which gives a definition (the `prompt`) and asks
+        /// for the term (the `answer`): the term is given, and the definition is asked for. Since
+        /// a definition is hard to recall word for word, mentioning at least half of its key terms
+        /// is accepted. The ID is kept, so the result counts toward the same question.
+        fn reversed(&self) -> Question {
+            let term = self.answer.first().map_or("", |term| term.trim());
+            Question {
+                id: Some(self.id()),
+                q_type: "ue".to_string(),
+                prompt: format!("Define '{}':", term),
+                choices: IndexSet::new(),
+                answer: vec![self.prompt.clone()],
+                pin_to_end: Vec::new(),
+                prompt_variants: Vec::new(),
+                followups: HashMap::new(),
+                ordered: false,
+                match_mode: Some(MatchMode::Keywords),
+                reversible: false,
+                always_include: false,
+                multiline: false,
+                params: BTreeMap::new(),
+                ..self.clone()
+            }
+        }
 
-                // Only print the explanation if one is provided; self-explanatory questions don't need explanation
-                if !question.explanation.is_empty() {
-                    println!("{}Explanation: {}{}", YELLOW_COLOR_CODE, question.explanation, RESET_COLOR_CODE);
-                }
-                // Always print reference(s)
-                println!("{}Reference(s):\n\t{}{}", CYAN_COLOR_CODE, question.refs.join("\n\t"), RESET_COLOR_CODE);
+        /// A copy of a multiple choice or multiple select question to be answered by typing the
+        /// text of any correct choice instead of picking it, for `--recall`. The choices aren't
+        /// displayed, and the answer is compared by its key terms, ignoring case.
+        fn recalled(&self) -> Question {
+            Question {
+                id: Some(self.id()),
+                q_type: "ue".to_string(),
+                choices: IndexSet::new(),
+                answer: self.answer.iter().map(|answer| strip_enumeration(answer).unwrap_or(answer).to_string()).collect(),
+                pin_to_end: Vec::new(),
+                ordered: false,
+                match_mode: Some(MatchMode::Keywords),
+                reversible: false,
+                always_include: false,
+                multiline: false,
+                params: BTreeMap::new(),
+                ..self.clone()
+            }
+        }
+
+        /// Whether the question is templated: its numbers are picked at random from its `params`
+        /// each time it's asked (see `instantiated`).
+        fn is_templated(&self) -> bool {
+            !self.params.is_empty()
+        }
 
-                // Sleep for a sec so that the user can see explanation & references
-                std::thread::sleep(std::time::Duration::from_secs(1));
+        /// A copy of a templated question with a value picked at random for each parameter. The
+        /// `{name}` placeholders of the prompt(s) and explanation are replaced by the values, and
+        /// each answer, a formula over the parameters (e.g., `2^(32 - mask) - 2`), is replaced by
+        /// its result, which is compared numerically unless a `match_mode` is given. The ID is
+        /// kept, so the result counts toward the same question.
+        fn instantiated(&self, rng: &mut StdRng) -> Question {
+            let values: BTreeMap<String, f64> = self.params.iter().map(|(name, param)| (name.clone(), param.pick(rng))).collect();
+            let substitute = |text: &str| {
+                values.iter().fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), &formula::format(*value)))
+            };
+            Question {
+                id: Some(self.id()),
+                prompt: substitute(&self.prompt),
+                // Formulas that can't be evaluated are reported by `validate`, and never match
+                answer: self.answer
+                    .iter()
+                    .map(|answer| formula::evaluate(answer, &values).map_or_else(|_| answer.clone(), formula::format))
+                    .collect(),
+                explanation: substitute(&self.explanation),
+                pin_to_end: Vec::new(),
+                prompt_variants: self.prompt_variants.iter().map(|variant| substitute(variant)).collect(),
+                ordered: false,
+                match_mode: Some(self.match_mode.unwrap_or(MatchMode::Numeric)),
+                reversible: false,
+                multiline: false,
+                params: BTreeMap::new(),
+                ..self.clone()
             }
+        }
 
-            // Ask whether or not to play again
-            match Self::input("\n\nPlay again (Y/n)? ").chars().next().unwrap_or('n') {
-                'y' | 'Y' => self.study(),
-                _ => {
-                    println!("\nYou got {}/{} questions correct.", num_correct, num_questions);
-                    println!("Great progress studying!");
-                }
+        /// The question as JSON in a form that's the same every time the exam is loaded: the
+        /// choices are sorted (their order doesn't affect grading), as are the keys of every
+        /// object, and an `@file:` explanation is kept as authored.
+        fn canonical(&self) -> serde_json::Value {
+            let mut value = serde_json::to_value(self).unwrap_or_default();
+            if let Some(serde_json::Value::Array(choices)) = value.get_mut("choices") {
+                choices.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
             }
+            value
         }
 
-        /// Helper function for displaying hints for user entry questions.
-        fn display_hints(hints_ref: &Vec<String>) {
-            hints_ref.iter().for_each(|hint| {
-                println!("{}\t{}Hint: {}{}{}", BLUE_COLOR_CODE, START_ITALICS, hint, END_ITALICS, RESET_COLOR_CODE);
-            })
+        /// How the answer to a user entry question is compared against its accepted answers; a
+        /// multi-line answer is hard to give word for word, so by default it only has to mention
+        /// enough key terms.
+        fn match_mode(&self) -> MatchMode {
+            match (self.match_mode, self.multiline) {
+                (Some(mode), _) => mode,
+                (None, true) => MatchMode::Keywords,
+                (None, false) => MatchMode::default(),
+            }
         }
 
-        /// Helper function that iterates over the `choices` field of the parameter `Question`.
-        /// The way a choice/option will be displayed depends on the `q_type` field.
-        fn display_choices_and_collect(question_ref: &Question) -> Vec<String> {
-            question_ref.choices.iter().enumerate().filter_map(|(index, choice)| {
-                match question_ref.q_type.as_str() {
-                    "mc" | "ms" => {
-                        println!("{}\t{}.) {}{}", BLUE_COLOR_CODE, (index as u8 + b'a') as char, choice, RESET_COLOR_CODE);
-                        Some(choice.to_string())
-                    },
-                    "ue" if !choice.is_empty() => {
-                        // Don't print hint(s) - let the user decide
-                        Some(choice.to_string())
-                    },
-                    _ => {
-                        // Executes if there's no hints provided for ue questions
-                        None
-                    },
-                }
-            })
-                .collect()
+        /// Position of a choice among those that must be displayed after every other choice, or
+        /// `None` if the choice can be placed anywhere. Choices listed in `pin_to_end` keep their
+        /// listed order, followed by choices like "All of the above" that refer to the others.
+        fn pinned_position(&self, choice: &str) -> Option<usize> {
+            let text = strip_enumeration(choice).unwrap_or(choice);
+            self.pin_to_end
+                .iter()
+                .position(|pinned| pinned == choice)
+                .or_else(|| {
+                    POSITIONAL_CHOICES
+                        .iter()
+                        .position(|positional| text.trim().trim_end_matches('.').eq_ignore_ascii_case(positional))
+                        .map(|position| self.pin_to_end.len() + position)
+                })
+        }
+    }
+
+    /// Strips a leading enumeration such as `a) `, `B. `, or `3. ` that was copied into the text of
+    /// a choice (e.g., from another tool), returning `None` if the choice doesn't start with one.
+    fn strip_enumeration(choice: &str) -> Option<&str> {
+        let trimmed = choice.trim_start();
+        let marker_len = trimmed.find([')', '.'])?;
+        let marker = &trimmed[..marker_len];
+        let is_enumeration = (marker.len() == 1 && marker.chars().all(|c| c.is_ascii_alphabetic()))
+            || ((1..=2).contains(&marker.len()) && marker.chars().all(|c| c.is_ascii_digit()));
+        let rest = &trimmed[marker_len + 1..];
+        if is_enumeration && rest.starts_with(char::is_whitespace) {
+            Some(rest.trim_start()).filter(|text| !text.is_empty())
+        } else {
+            None
+        }
+    }
+
+    /// Width of the terminal in columns, or 80 when it can't be detected.
+    pub fn terminal_width() -> usize {
+        terminal_size().map_or(80, |(Width(width), _)| width as usize)
+    }
+
+    /// Splits text into lines of at most `width` characters, breaking between words; line breaks
+    /// already in the text are kept, and words longer than `width` get a line of their own.
+    fn word_wrap(text: &str, width: usize) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Parses a numeric answer, ignoring surrounding whitespace and digit separators (e.g.,
+    /// "1,000" or "1_000"); `None` if it isn't a number.
+    fn parse_number(text: &str) -> Option<f64> {
+        text.trim().replace([',', '_'], "").parse::<f64>().ok().filter(|number| number.is_finite())
+    }
+
+    /// 64-bit FNV-1a hash; unlike `DefaultHasher`, the result is guaranteed to be the same across
+    /// Rust releases, which is required for identifiers that get written to disk.
+    fn stable_hash(text: &str) -> u64 {
+        text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    }
+
+    /// Logs every question answered during a run (study session) to the attempts file, under a
+    /// run ID picked when the run starts. Problems with the file are reported once, after which
+    /// nothing more is logged for the run.
+    struct AttemptLog<'a> {
+        /// Name of the exam the questions belong to
+        exam: &'a str,
+        /// ID of the run, or `None` once logging failed
+        run: Cell<Option<u64>>,
+    }
+
+    impl<'a> AttemptLog<'a> {
+        fn start(exam: &'a str) -> AttemptLog<'a> {
+            let run = attempts::next_run()
+                .inspect_err(|e| errln!("{}Unable to read the attempts log; answers won't be logged:\t{}{}", color(Role::Warning), e, color(Role::Reset)))
+                .ok();
+            AttemptLog { exam, run: Cell::new(run) }
+        }
+
+        /// Logs the given answer to a question.
+        fn record(&self, question: &Question, given: &[String], is_correct: bool) {
+            let Some(run) = self.run.get() else { return };
+            if let Err(e) = attempts::append(run, self.exam, &question.id(), given, &question.answer, is_correct) {
+                errln!("{}Unable to log the answer; answers won't be logged for the rest of the session:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                self.run.set(None);
+            }
+        }
+    }
+
+    /// Automatically bookmarks questions that give the user trouble: those missed more than a
+    /// number of times (including previous sessions), or answered too slowly.
+    struct AutoFlagger<'a> {
+        /// Name of the exam the questions belong to
+        exam: &'a str,
+        /// A question is bookmarked once it has been missed more than this many times
+        max_misses: usize,
+        /// A question is bookmarked when answering it takes longer than this
+        max_seconds: f64,
+        /// Number of times each question (by ID) has been missed, including previous sessions
+        misses: HashMap<String, usize>,
+        /// Number of questions that were bookmarked during this session
+        num_flagged: usize,
+    }
+
+    impl<'a> AutoFlagger<'a> {
+        fn new(exam: &'a str, settings: &Settings) -> Self {
+            AutoFlagger {
+                exam,
+                max_misses: settings.flag_misses.unwrap_or(DEFAULT_FLAG_MISSES),
+                max_seconds: settings.flag_seconds.unwrap_or(DEFAULT_FLAG_SECONDS) as f64,
+                // Without a readable history, only the misses from this session are counted
+                misses: history::misses(exam).unwrap_or_default(),
+                num_flagged: 0,
+            }
+        }
+
+        /// Records the user's answer to a question, and bookmarks the question if it crossed
+        /// either threshold.
+        fn observe(&mut self, question: &Question, is_correct: bool, seconds: f64) {
+            let id = question.id();
+            let misses = self.misses.entry(id.clone()).or_default();
+            *misses += usize::from(!is_correct);
+            let reason = if *misses > self.max_misses {
+                format!("missed {} times", misses)
+            } else if seconds > self.max_seconds {
+                format!("answered in {:.0} seconds", seconds)
+            } else {
+                return;
+            };
+            match bookmarks::add(self.exam, &id) {
+                Ok(true) => {
+                    self.num_flagged += 1;
+                    outln!("{}Bookmarked this question ({}){}", color(Role::Info), reason, color(Role::Reset));
+                },
+                Ok(false) => {},
+                Err(e) => errln!("{}Unable to bookmark question:\t{}{}", color(Role::Warning), e, color(Role::Reset)),
+            }
+        }
+
+        /// Number of times a question will have been missed (including previous sessions) once the
+        /// given answer to it is observed.
+        fn misses_after(&self, question: &Question, is_correct: bool) -> usize {
+            self.misses.get(&question.id()).copied().unwrap_or_default() + usize::from(!is_correct)
+        }
+
+        /// Prints how many questions were bookmarked during the session, if any.
+        fn report(&self) {
+            if self.num_flagged > 0 {
+                outln!("{}{} question(s) were automatically bookmarked this session; study them with --bookmarked{}",
+                    color(Role::Info), self.num_flagged, color(Role::Reset));
+            }
+        }
+    }
+
+    impl Exam {
+        /// Attempts to create an Exam if an exam JSON file exists and is properly formatted. The
+        /// exam files listed after the selected one are returned too, so that the user can continue
+        /// with the next exam. When `quick` is set (the program was run without any arguments) and
+        /// there's only one exam file to choose from, it's studied right away.
+        pub fn new(use_default_dir: bool, random: bool, quick: bool) -> Option<(Self, Vec<PathBuf>)> {
+            match env::current_dir() {
+                Ok(cwd) if Self::create_asset_dir(&cwd) => {
+                    if let Some(exam) = quick.then(|| Self::quick_quiz(&cwd)).flatten() {
+                        return Some((exam, Vec::new()));
+                    }
+                    // Offering to resume would defeat the purpose of skipping the questions
+                    let resumed = if use_default_dir || random { None } else { Self::offer_resume() };
+                    match resumed {
+                        Some(exam) => Some((exam, Vec::new())),
+                        None => Some(Self::get_exam(&cwd, use_default_dir, random)),
+                    }
+                },
+                _ => {
+                    errln!("{}Unable to create Exam{}", color(Role::Error), color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Attempts to create an Exam from the exam file at the given path, skipping the
+        /// interactive directory flow.
+        pub fn from_file(path: &Path) -> Option<Self> {
+            match Self::load_exam(path, &mut Vec::new()) {
+                Ok(exam) => Some(exam),
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), e, color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Loads and validates each of the given exam files, printing any problems that are
+        /// found; every exam file in the `assets` directory is checked when no paths are given.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `true` if every exam file loaded without any problems.
+        pub fn check(paths: &[PathBuf]) -> bool {
+            let paths: Vec<PathBuf> = if paths.is_empty() {
+                match env::current_dir().and_then(|cwd| fs::read_dir(cwd.join(ASSETS_DIR))) {
+                    Ok(entries) => {
+                        let mut paths: Vec<PathBuf> = entries
+                            .filter_map(Result::ok)
+                            .map(|e| e.path())
+                            .filter(|path| path.is_file() && Self::is_exam_file(path))
+                            .collect();
+                        paths.sort();
+                        paths
+                    },
+                    Err(e) => {
+                        errln!("{}{}{}", color(Role::Error), Self::directory_error_message(&e, Path::new(ASSETS_DIR)), color(Role::Reset));
+                        return false;
+                    },
+                }
+            } else {
+                paths.to_vec()
+            };
+            if paths.is_empty() {
+                outln!("There are no exam files to check");
+            }
+
+            let mut all_valid = true;
+            for path in paths.iter() {
+                let validation = match Self::load_exam(path, &mut Vec::new()) {
+                    Ok(exam) => exam.validate(),
+                    Err(e) => Validation { problems: vec![e], warnings: Vec::new() },
+                };
+                if !validation.problems.is_empty() {
+                    all_valid = false;
+                    outln!("{}FAIL{}\t{}", color(Role::Error), color(Role::Reset), path.display());
+                } else if !validation.warnings.is_empty() {
+                    outln!("{}WARN{}\t{}", color(Role::Warning), color(Role::Reset), path.display());
+                } else {
+                    outln!("{}OK{}\t{}", color(Role::Success), color(Role::Reset), path.display());
+                }
+                validation.problems.iter().for_each(|problem| outln!("\t- {}", problem));
+                validation.warnings.iter().for_each(|warning| outln!("\t- (advisory) {}", warning));
+            }
+            all_valid
+        }
+
+        /// Helper function that lists the questions sorted by ID; unlike the authored order they are
+        /// stored in, this order doesn't change when questions are reordered or moved between
+        /// included files, so the same number always refers to the same question.
+        fn sorted_questions(&self) -> Vec<&Question> {
+            let mut questions: Vec<&Question> = self.questions.iter().collect();
+            questions.sort_by_key(|question| question.id());
+            questions
+        }
+
+        /// Helper function that lists the questions in the order a session starts from: the authored
+        /// order for exams that preserve it, otherwise sorted by ID so that shuffling them only
+        /// depends on the generator's seed.
+        fn session_questions(&self) -> Vec<&Question> {
+            if self.preserve_order {
+                self.questions.iter().collect()
+            } else {
+                self.sorted_questions()
+            }
+        }
+
+        /// Displays a single question, picked by its position among the questions sorted by ID,
+        /// so that authors can check how it renders; it can then be answered (or skipped with
+        /// `quit`) without the result being recorded.
+        ///
+        /// # Argument
+        ///
+        /// * `number` - the 1-based position of the question.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `false` if there is no question at that position.
+        pub fn preview(&self, number: usize, settings: &Settings) -> bool {
+            let questions = self.sorted_questions();
+            let Some(question) = number.checked_sub(1).and_then(|index| questions.get(index)) else {
+                errln!("{}There is no question {}; the exam has {} questions{}", color(Role::Error), number, questions.len(), color(Role::Reset));
+                return false;
+            };
+            let mut rng = StdRng::from_entropy();
+            let instantiated: Question;
+            let question = if question.is_templated() {
+                instantiated = question.instantiated(&mut rng);
+                &instantiated
+            } else {
+                question
+            };
+            outln!("\n{}[Question {}/{} - ID {}]{}", color(Role::Prompt), number, questions.len(), question.id(), color(Role::Reset));
+            outln!("{}", math::render(question.pick_prompt(&mut rng)));
+            loop {
+                match self.ask(question, settings, &mut rng) {
+                    Ok(response) => {
+                        self.give_feedback(question, response.is_correct, None, settings);
+                        return true;
+                    },
+                    Err(SessionCommand::Back) => {
+                        errln!("{}There is no previous question to go back to!{}", color(Role::Error), color(Role::Reset));
+                    },
+                    Err(SessionCommand::Quit | SessionCommand::Skip) => return true,
+                }
+            }
+        }
+
+        /// Prints the answer key: every question (ordered by ID, and numbered like `--question`)
+        /// followed by its correct answers, wrapped to the terminal's width, without asking any.
+        pub fn print_key(&self) {
+            outln!("{}Answer key: {}{}", color(Role::Success), self.name, color(Role::Reset));
+            let questions = self.sorted_questions();
+            let width = terminal_width();
+            for (index, question) in questions.iter().enumerate() {
+                outln!("\n{}[Question {}/{} - ID {}]{}", color(Role::Prompt), index + 1, questions.len(), question.id(), color(Role::Reset));
+                word_wrap(&math::render(&question.prompt), width).iter().for_each(|line| outln!("{}", line));
+                let answers: Vec<&str> = question.answer.iter().map(|answer| answer.trim()).collect();
+                // Every answer of a multiple select question is required, while user entry questions
+                // accept any one of theirs
+                let key = match question.q_type.as_str() {
+                    "ms" if question.ordered => format!("{} (in this order)", answers.join(", ")),
+                    "ms" => answers.join(", "),
+                    _ => match answers.split_first() {
+                        Some((first, [])) => first.to_string(),
+                        Some((first, rest)) => format!("{} (also accepted: {})", first, rest.join(", ")),
+                        None => String::new(),
+                    },
+                };
+                // Indented under the prompt, so the lines are shortened to fit
+                for line in word_wrap(&format!("Answer: {}", math::render(&key)), width.saturating_sub(4).max(20)) {
+                    outln!("    {}{}{}", color(Role::Explanation), line, color(Role::Reset));
+                }
+            }
+        }
+
+        /// Grades a file of submitted answers without asking any questions, printing the result of
+        /// each question followed by the total score. The file is a JSON object that maps question
+        /// IDs to the submitted answer: the text of the chosen choice, or for multiple select
+        /// questions, an array (or a comma-separated list) of the chosen choices. Questions without
+        /// a submitted answer count as incorrect. Response files written by `--collect` are only
+        /// graded if they were collected from the same version of the exam.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `false` if the response file couldn't be read, or is for a different exam.
+        pub fn grade_responses(&self, path: &Path) -> bool {
+            let responses: HashMap<String, Submission> = match fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()))
+            {
+                Ok(ResponseFile::Plain(responses)) => responses,
+                Ok(ResponseFile::Bundle(bundle)) => {
+                    if bundle.exam_hash != self.content_hash() {
+                        errln!("{}The responses in {} were collected for a different version of the exam ('{}'), so they can't be graded{}",
+                            color(Role::Error), path.display(), bundle.exam, color(Role::Reset));
+                        return false;
+                    }
+                    bundle.answers.into_iter().collect()
+                },
+                Err(e) => {
+                    errln!("{}Unable to read responses {}:\t{}{}", color(Role::Error), path.display(), e, color(Role::Reset));
+                    return false;
+                },
+            };
+
+            outln!("{}Grading responses for: {}{}", color(Role::Success), self.name, color(Role::Reset));
+            let questions = self.sorted_questions();
+            let mut num_correct = 0;
+            for question in questions.iter() {
+                let id = question.id();
+                let Some(submission) = responses.get(&id) else {
+                    outln!("{}[MISSING]{} {}: {}", color(Role::Error), color(Role::Reset), id, question.prompt);
+                    continue;
+                };
+                let entries: Vec<String> = match submission {
+                    Submission::One(text) if question.q_type == "ms" => text.split(',').map(str::to_string).collect(),
+                    Submission::One(text) => vec![text.clone()],
+                    Submission::Many(texts) => texts.clone(),
+                };
+                // Choices are named by their text; anything that doesn't name one is graded as written
+                let choices: Vec<String> = question.choices.iter().cloned().collect();
+                let given: Vec<String> = entries
+                    .iter()
+                    .map(|entry| match question.q_type.as_str() {
+                        "mc" | "ms" => Self::match_choice_text(entry, &choices).cloned().unwrap_or_else(|| entry.trim().to_string()),
+                        _ => entry.trim().to_string(),
+                    })
+                    .collect();
+                if self.grade(question, &given) {
+                    num_correct += 1;
+                    outln!("{}[CORRECT]{} {}: {}", color(Role::Success), color(Role::Reset), id, question.prompt);
+                } else {
+                    outln!("{}[WRONG]{} {}: {}", color(Role::Error), color(Role::Reset), id, question.prompt);
+                    outln!("\tSubmitted: {}", given.join(", "));
+                    outln!("\tExpected: {}", question.answer.join(", "));
+                }
+            }
+            let known_ids: HashSet<String> = questions.iter().map(|question| question.id()).collect();
+            let mut unknown: Vec<&String> = responses.keys().filter(|id| !known_ids.contains(*id)).collect();
+            unknown.sort();
+            for id in unknown {
+                errln!("{}Ignoring a response to unknown question ID '{}'{}", color(Role::Warning), id, color(Role::Reset));
+            }
+
+            let percent = if questions.is_empty() { 0.0 } else { 100.0 * num_correct as f64 / questions.len() as f64 };
+            outln!("\nTotal: {}/{} questions correct ({:.1}%).", num_correct, questions.len(), percent);
+            true
+        }
+
+        /// Presents every question (ordered by ID) without revealing whether the answers are
+        /// correct, then writes the answers to a response file that can be graded later with
+        /// `--answers`. `back` returns to the previous question, `skip` leaves a question
+        /// unanswered, and `quit` stops early, saving the answers given so far.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `false` if the response file couldn't be written.
+        pub fn collect_responses(&self, path: &Path, settings: &Settings) -> bool {
+            outln!("{}Collecting responses for: {}{}", color(Role::Success), self.name, color(Role::Reset));
+            outln!("Your answers won't be graded until the response file is handed in.");
+            let questions = self.sorted_questions();
+            let mut rng = StdRng::from_entropy();
+            let mut answers: BTreeMap<String, Submission> = BTreeMap::new();
+            let mut index = 0;
+            while let Some(question) = questions.get(index) {
+                outln!("\n{}[Question {}/{}]{}", color(Role::Prompt), index + 1, questions.len(), color(Role::Reset));
+                outln!("{}", math::render(question.pick_prompt(&mut rng)));
+                match self.collect_answer(question, settings, &mut rng, &mut 0) {
+                    Ok(given) => {
+                        let submission = match question.q_type.as_str() {
+                            "ms" => Submission::Many(given),
+                            _ => Submission::One(given.join(", ")),
+                        };
+                        answers.insert(question.id(), submission);
+                    },
+                    Err(SessionCommand::Back) => {
+                        match index.checked_sub(1) {
+                            Some(previous) => index = previous,
+                            None => errln!("{}There is no previous question to go back to!{}", color(Role::Error), color(Role::Reset)),
+                        }
+                        continue;
+                    },
+                    Err(SessionCommand::Skip) => {
+                        answers.remove(&question.id());
+                    },
+                    Err(SessionCommand::Quit) => break,
+                }
+                index += 1;
+            }
+
+            let bundle = ResponseBundle { exam: self.name.clone(), exam_hash: self.content_hash(), answers };
+            match serde_json::to_string_pretty(&bundle)
+                .map_err(|e| e.to_string())
+                .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()))
+            {
+                Ok(()) => {
+                    outln!("\n{}Saved {} of {} answers to {}; hand it in to be graded.{}",
+                        color(Role::Success), bundle.answers.len(), questions.len(), path.display(), color(Role::Reset));
+                    true
+                },
+                Err(e) => {
+                    errln!("{}Unable to save responses to {}:\t{}{}", color(Role::Error), path.display(), e, color(Role::Reset));
+                    false
+                },
+            }
+        }
+
+        /// Hash of the exam's questions (in order of their IDs, each in its canonical form),
+        /// identifying this version of the exam in response files.
+        fn content_hash(&self) -> String {
+            let questions: Vec<serde_json::Value> = self.sorted_questions().into_iter().map(Question::canonical).collect();
+            let questions = serde_json::to_string(&questions).unwrap_or_default();
+            format!("{:016x}", stable_hash(&questions))
+        }
+
+        /// Checks the exam for authoring mistakes that would make questions impossible to study,
+        /// along with advisory warnings about content that could be improved.
+        pub fn validate(&self) -> Validation {
+            let mut problems: Vec<String> = Vec::new();
+            let mut ids: HashSet<String> = HashSet::new();
+            let all_ids: HashSet<String> = self.questions.iter().map(Question::id).collect();
+            // Sorted so that problems are reported in the same order every time
+            for question in self.sorted_questions() {
+                match question.q_type.as_str() {
+                    "mc" | "ms" => {
+                        for answer in question.answer.iter().filter(|answer| !question.choices.contains(*answer)) {
+                            problems.push(format!("'{}': answer '{}' doesn't match any choice", question.prompt, answer));
+                        }
+                        if question.choices.len() < 2 {
+                            problems.push(format!("'{}': {} questions need at least 2 choices, but there are {}",
+                                question.prompt, question.q_type, question.choices.len()));
+                        }
+                        match (question.q_type.as_str(), question.answer.len()) {
+                            ("mc", 1) | ("ms", 1..) => {},
+                            ("mc", num_answers) => {
+                                problems.push(format!("'{}': mc questions need exactly 1 answer, but there are {}", question.prompt, num_answers));
+                            },
+                            _ => problems.push(format!("'{}': ms questions need at least 1 answer, but there are none", question.prompt)),
+                        }
+                    },
+                    "ue" => {},
+                    other => problems.push(format!("'{}': unrecognized q_type '{}'", question.prompt, other)),
+                }
+                for pinned in question.pin_to_end.iter().filter(|pinned| !question.choices.contains(*pinned)) {
+                    problems.push(format!("'{}': pinned choice '{}' doesn't match any choice", question.prompt, pinned));
+                }
+                if question.ordered && question.q_type != "ms" {
+                    problems.push(format!("'{}': only multiple select questions can be ordered", question.prompt));
+                }
+                if question.multiline && question.q_type != "ue" {
+                    problems.push(format!("'{}': only user entry questions can have multi-line answers", question.prompt));
+                }
+                if question.reversible && (question.q_type != "ue" || question.answer.is_empty()) {
+                    problems.push(format!("'{}': only user entry questions with an answer can be reversible", question.prompt));
+                }
+                match question.match_mode {
+                    Some(_) if question.q_type != "ue" => {
+                        problems.push(format!("'{}': only user entry questions can have a match_mode", question.prompt));
+                    },
+                    Some(MatchMode::Regex) => {
+                        for (answer, e) in question.answer.iter().filter_map(|answer| Self::compile_pattern(answer).err().map(|e| (answer, e))) {
+                            problems.push(format!("'{}': answer '{}' isn't a valid regular expression: {}", question.prompt, answer, e));
+                        }
+                    },
+                    // A templated question's answers are formulas, which are checked below
+                    Some(MatchMode::Numeric) if !question.is_templated() => {
+                        for answer in question.answer.iter().filter(|answer| parse_number(answer).is_none()) {
+                            problems.push(format!("'{}': answer '{}' isn't a number", question.prompt, answer));
+                        }
+                    },
+                    _ => {},
+                }
+                if question.is_templated() {
+                    problems.extend(Self::validate_template(question));
+                }
+                for followup in question.followups.values().filter(|followup| !all_ids.contains(*followup)) {
+                    problems.push(format!("'{}': follow-up question '{}' doesn't exist", question.prompt, followup));
+                }
+                let id = question.id();
+                if !ids.insert(id.clone()) {
+                    problems.push(format!("'{}': duplicate question ID '{}'", question.prompt, id));
+                }
+            }
+            if self.default_count == Some(0) {
+                problems.push("default_count must be at least 1".to_string());
+            }
+            let mut pooled: HashSet<&str> = HashSet::new();
+            for pool in self.pools.iter() {
+                if !pooled.insert(&pool.section) {
+                    problems.push(format!("pool '{}' is declared more than once", pool.section));
+                }
+                if pool.draw == 0 {
+                    problems.push(format!("pool '{}' must draw at least 1 question", pool.section));
+                }
+                let available = self.questions.iter().filter(|question| question.section.as_deref() == Some(pool.section.as_str())).count();
+                if available < pool.draw {
+                    problems.push(format!("pool '{}' draws {} questions, but the section only has {}", pool.section, pool.draw, available));
+                }
+            }
+            let mut warnings = self.overused_distractors();
+            if self.preserve_order && !self.pools.is_empty() {
+                warnings.push("preserve_order keeps pools from drawing at random; the first questions of each section are always drawn".to_string());
+            }
+            Validation { problems, warnings }
+        }
+
+        /// Helper function that checks a templated question: it must be a user entry question,
+        /// every parameter must have a value, each answer formula must evaluate, and each `{name}`
+        /// placeholder of the prompt must name a parameter.
+        fn validate_template(question: &Question) -> Vec<String> {
+            let mut problems: Vec<String> = Vec::new();
+            if question.q_type != "ue" {
+                problems.push(format!("'{}': only user entry questions can have params", question.prompt));
+            }
+            let mut values: BTreeMap<String, f64> = BTreeMap::new();
+            for (name, param) in question.params.iter() {
+                match (param, param.first()) {
+                    (Param::Range { min, max }, _) if min > max => {
+                        problems.push(format!("'{}': param '{}' has a min ({}) greater than its max ({})", question.prompt, name, min, max));
+                    },
+                    (_, Some(value)) => {
+                        values.insert(name.clone(), value);
+                    },
+                    (_, None) => problems.push(format!("'{}': param '{}' has no values", question.prompt, name)),
+                }
+            }
+            // Formulas can only be evaluated once every parameter has a value
+            if values.len() == question.params.len() {
+                for (answer, e) in question.answer.iter().filter_map(|answer| formula::evaluate(answer, &values).err().map(|e| (answer, e))) {
+                    problems.push(format!("'{}': answer formula '{}' can't be evaluated: {}", question.prompt, answer, e));
+                }
+            }
+            let placeholder = Regex::new(r"\{(\w+)\}").expect("the placeholder pattern is valid");
+            for name in placeholder.captures_iter(&question.prompt).map(|captures| captures[1].to_string()) {
+                if !question.params.contains_key(&name) {
+                    problems.push(format!("'{}': placeholder '{{{}}}' doesn't name a param", question.prompt, name));
+                }
+            }
+            problems
+        }
+
+        /// Helper function that finds distractors (incorrect choices) whose text is reused across
+        /// many questions; a learner can pick up on these rather than learning the content.
+        fn overused_distractors(&self) -> Vec<String> {
+            // Maps normalized distractor text to the number of questions using it
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for question in self.questions.iter().filter(|q| q.q_type == "mc" || q.q_type == "ms") {
+                let distractors: HashSet<String> = question.choices
+                    .iter()
+                    .filter(|choice| !question.answer.contains(*choice) && question.pinned_position(choice).is_none())
+                    .map(|choice| choice.trim().to_lowercase())
+                    .collect();
+                for distractor in distractors {
+                    *counts.entry(distractor).or_default() += 1;
+                }
+            }
+            let mut overused: Vec<(String, usize)> = counts
+                .into_iter()
+                .filter(|(_, count)| *count >= DISTRACTOR_REUSE_LIMIT)
+                .collect();
+            // Most reused first, then alphabetically so the report is stable
+            overused.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            overused
+                .into_iter()
+                .map(|(distractor, count)| format!("distractor '{}' is reused in {} questions", distractor, count))
+                .collect()
+        }
+
+        /// Interactively adds questions to the exam file at `path`, creating the file if it
+        /// doesn't exist yet. Questions are only written to the file when the user saves.
+        pub fn author(path: &Path) {
+            let mut exam = if path.exists() {
+                match Self::read_exam_file(path) {
+                    Ok(exam) => exam,
+                    Err(e) => {
+                        errln!("{}{}{}", color(Role::Error), e, color(Role::Reset));
+                        return;
+                    },
+                }
+            } else {
+                Exam {
+                    name: Self::input("Enter the exam name: "),
+                    questions: IndexSet::new(),
+                    simulation: None,
+                    include: Vec::new(),
+                    synonyms: HashMap::new(),
+                    explanation_files: RefCell::default(),
+                    patterns: RefCell::default(),
+                    source: None,
+                    quick_count: None,
+                    instructions: None,
+                    normalize: Vec::new(),
+                    pools: Vec::new(),
+                    default_count: None,
+                    feedback: Feedback::default(),
+                    preserve_order: false,
+                }
+            };
+            outln!("\n{}Authoring: {} ({} questions){}", color(Role::Success), exam.name, exam.questions.len(), color(Role::Reset));
+            // Whether questions were added since the exam was last saved (or loaded)
+            let mut dirty = false;
+            loop {
+                match Self::input("\n[a]dd a question, [s]ave, or [q]uit? ").to_ascii_lowercase().as_str() {
+                    "a" | "add" => {
+                        if exam.questions.insert(Self::input_question()) {
+                            dirty = true;
+                        } else {
+                            errln!("{}The exam already has this question; it wasn't added again{}", color(Role::Warning), color(Role::Reset));
+                        }
+                    },
+                    "s" | "save" => {
+                        if exam.save_authored(path) {
+                            dirty = false;
+                        }
+                    },
+                    "q" | "quit" => {
+                        // Unsaved questions are only discarded when the user says so; quitting is
+                        // called off if saving fails
+                        if !dirty || Self::input_optional("You have unsaved changes - save before quitting? (Y/n) ").to_ascii_lowercase().starts_with('n') {
+                            break;
+                        }
+                        if exam.save_authored(path) {
+                            break;
+                        }
+                    },
+                    _ => errln!("{}Please enter a valid option!{}", color(Role::Error), color(Role::Reset)),
+                }
+            }
+        }
+
+        /// Helper function that saves the exam being authored to `path`, reporting the outcome.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - whether the exam was saved.
+        fn save_authored(&self, path: &Path) -> bool {
+            match self.save(path) {
+                Ok(()) => {
+                    outln!("{}Saved {} questions to {}{}", color(Role::Success), self.questions.len(), path.display(), color(Role::Reset));
+                    true
+                },
+                Err(e) => {
+                    errln!("{}Unable to save exam:\t{}{}", color(Role::Error), e, color(Role::Reset));
+                    false
+                },
+            }
+        }
+
+        /// Helper function that walks the user through entering each field of a new question.
+        fn input_question() -> Question {
+            let q_type = loop {
+                match Self::input("Question type (mc, ms, ue): ").to_ascii_lowercase().as_str() {
+                    q_type @ ("mc" | "ms" | "ue") => break q_type.to_string(),
+                    _ => errln!("{}Please enter 'mc', 'ms', or 'ue'!{}", color(Role::Error), color(Role::Reset)),
+                }
+            };
+            let prompt = Self::input("Prompt: ");
+            let (choices, answer) = if q_type == "ue" {
+                let mut hints = Self::input_list("Hint");
+                if hints.is_empty() {
+                    // An empty string indicates that there are no hints
+                    hints.push(String::new());
+                }
+                let answers = loop {
+                    let answers = Self::input_list("Accepted answer");
+                    if !answers.is_empty() {
+                        break answers;
+                    }
+                    errln!("{}At least one answer is required!{}", color(Role::Error), color(Role::Reset));
+                };
+                (hints, answers)
+            } else {
+                let choices = loop {
+                    let choices = Self::input_list("Choice");
+                    if choices.len() >= 2 {
+                        break choices;
+                    }
+                    errln!("{}At least two choices are required!{}", color(Role::Error), color(Role::Reset));
+                };
+                for (index, choice) in choices.iter().enumerate() {
+                    outln!("{}\t{}.) {}{}", color(Role::Hint), (index as u8 + b'a') as char, choice, color(Role::Reset));
+                }
+                let answers: Vec<String> = loop {
+                    let prompt = if q_type == "mc" { "Correct choice: " } else { "Correct choices (comma-separated): " };
+                    let selected: Option<Vec<String>> = Self::input(prompt)
+                        .split(',')
+                        .map(|choice| Self::resolve_choice(choice, &choices).cloned())
+                        .collect();
+                    match selected {
+                        Some(selected) if q_type == "ms" || selected.len() == 1 => break selected,
+                        _ => errln!("{}Please enter valid choice(s)!{}", color(Role::Error), color(Role::Reset)),
+                    }
+                };
+                (choices, answers)
+            };
+            let ordered = q_type == "ms" && Self::input_optional("Must the choices be selected in order? (y/N): ").eq_ignore_ascii_case("y");
+            Question {
+                q_type,
+                prompt,
+                choices: choices.into_iter().collect(),
+                answer,
+                explanation: Self::input_optional("Explanation (optional): "),
+                refs: Self::input_list("Reference"),
+                ordered,
+                ..Question::default()
+            }
+        }
+
+        /// Helper function that collects a list of entries from the user, one per line, until
+        /// the user enters `done`.
+        fn input_list(item: &str) -> Vec<String> {
+            let mut items: Vec<String> = Vec::new();
+            loop {
+                let entry = Self::input(&format!("{} #{} (or 'done'): ", item, items.len() + 1));
+                if entry.eq_ignore_ascii_case("done") {
+                    break items;
+                }
+                items.push(entry);
+            }
+        }
+
+        /// Helper function like `input`, except that an empty entry is accepted.
+        fn input_optional(prompt: &str) -> String {
+            let mut temp: String = String::new();
+            print!("{}", prompt);
+            stdout().flush().expect("Unable to flush stdout...");
+            stdin().read_line(&mut temp).expect("Unable to read from stdin");
+            transcribe(&format!("{}{}", prompt, temp.trim()));
+            temp.trim().to_string()
+        }
+
+        /// Attempts to create an Exam by downloading a JSON-formatted exam file over HTTPS; this
+        /// skips the local directory flow entirely.
+        ///
+        /// # Argument
+        ///
+        /// * `url` - the HTTPS URL of the JSON exam file.
+        ///
+        /// # Returns
+        ///
+        /// * `Option<Exam>` - `Some` if the file was downloaded and parsed successfully; otherwise an
+        ///   error message is printed to `stderr` and `None` is returned.
+        pub fn from_url(url: &str) -> Option<Self> {
+            if !url.starts_with("https://") {
+                errln!("{}Only HTTPS URLs are supported for downloading exams{}", color(Role::Error), color(Role::Reset));
+                return None;
+            }
+            outln!("Downloading exam from {}...", url);
+            let response = match ureq::get(url).call() {
+                Ok(response) if response.status() == 200 => response,
+                Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                    errln!("{}Server responded with {} {}{}", color(Role::Error), response.status(), response.status_text(), color(Role::Reset));
+                    return None;
+                },
+                Err(ureq::Error::Transport(e)) => {
+                    errln!("{}Unable to download exam:\t{}{}", color(Role::Error), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            // Read one byte past the limit so that oversized files can be detected
+            let mut body: Vec<u8> = Vec::new();
+            if let Err(e) = response.into_reader().take(MAX_DOWNLOAD_BYTES + 1).read_to_end(&mut body) {
+                errln!("{}Unable to read downloaded exam:\t{}{}", color(Role::Error), e, color(Role::Reset));
+                return None;
+            }
+            if body.len() as u64 > MAX_DOWNLOAD_BYTES {
+                errln!("{}Exam file exceeds the {} MiB download limit{}", color(Role::Error), MAX_DOWNLOAD_BYTES / (1024 * 1024), color(Role::Reset));
+                return None;
+            }
+            match serde_json::from_slice::<Exam>(&body) {
+                Ok(exam) => {
+                    if !exam.include.is_empty() {
+                        errln!("{}Included exam files can't be resolved for downloaded exams; they will be ignored{}", color(Role::Warning), color(Role::Reset));
+                    }
+                    Some(exam)
+                },
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), Self::parse_error_message("The downloaded exam", &e), color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Helper function that ensures the creation of the default `assets` directory for storing
+        /// JSON-formatted exam files.
+        ///
+        /// # Argument
+        ///
+        /// * `cwd` - a reference to the current working directory as a `Path` reference.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - If the `assets` directory already exists, or if the `assets` directory was
+        ///   created without any errors, then the program will print out the applicable message and
+        ///   return `true` - otherwise the program will print an error message to `stderr` and return
+        ///   `false`. An existing `assets` directory that can't be read only produces a warning,
+        ///   since a different directory can still be chosen.
+        fn create_asset_dir(cwd: &Path) -> bool {
+            let assets_dir = cwd.join(ASSETS_DIR);
+            match fs::create_dir(&assets_dir) {
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    outln!("The {} directory already exists; no need to create it...", ASSETS_DIR);
+                    if let Err(e) = fs::read_dir(&assets_dir) {
+                        errln!("{}{}{}", color(Role::Warning), Self::directory_error_message(&e, &assets_dir), color(Role::Reset));
+                    }
+                    true
+                },
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), Self::directory_error_message(&e, &assets_dir), color(Role::Reset));
+                    false
+                },
+                Ok(()) => {
+                    outln!("Created the {} directory", ASSETS_DIR);
+                    true
+                },
+            }
+        }
+
+        /// Gets the appropriate exam directory from the user for the study session, attempts to
+        /// get the appropriate `Exam` via an `Option` depending on whether the JSON file exists.
+        /// When `use_default_dir` is set, the `assets` directory is searched without asking, and
+        /// its only exam file (if there's exactly one) is loaded without asking either; should that
+        /// fail, the regular interactive flow is used instead. When `random` is set, an exam file is
+        /// picked at random instead of asking for one (which can also be requested at the prompt).
+        /// Entering 'a' at the prompt studies every exam file of the directory as a single exam.
+        fn get_exam(cwd: &Path, mut use_default_dir: bool, random: bool) -> (Exam, Vec<PathBuf>) {
+            let result = 'select: loop {
+                let assets_dir: PathBuf = if use_default_dir {
+                    cwd.join(ASSETS_DIR)
+                } else {
+                    Self::select_asset_directory(cwd)
+                };
+                let skip_selection = use_default_dir;
+                use_default_dir = false;
+                match Self::display_and_collect_available_exams(assets_dir, false) {
+                    Some(empty_dir) if empty_dir.is_empty() => {
+                        errln!("{}There are no available exam files in chosen directory{}", color(Role::Error), color(Role::Reset));
+                    },
+                    Some(exam_dir) => {
+                        // Get the appropriate exam from the list provided
+                        let exam_path = match exam_dir.as_slice() {
+                            [only_exam] if skip_selection => only_exam,
+                            _ if random => Self::pick_random_exam(&exam_dir),
+                            _ => loop {
+                                let prompt = "Enter the exam number (e.g., '1', '2', '3', ...), 'r' for a random exam, or 'a' for all exams: ";
+                                let selection = Self::input(prompt);
+                                match selection.to_ascii_lowercase().as_str() {
+                                    "r" | "random" => break Self::pick_random_exam(&exam_dir),
+                                    "a" | "all" => match Self::combine_exams(&exam_dir) {
+                                        Some(exam) => break 'select (exam, Vec::new()),
+                                        None => continue,
+                                    },
+                                    _ => {},
+                                }
+                                let index = selection.parse::<usize>().unwrap_or(usize::MAX) - 1;
+                                match exam_dir.get(index) {
+                                    Some(exam) => break exam,
+                                    _ => errln!("{}Please make a valid selection!{}", color(Role::Error), color(Role::Reset)),
+                                }
+                            },
+                        };
+                        // Open the file and attempt to parse the contents into an exam
+                        match Self::load_exam(exam_path, &mut Vec::new()) {
+                            Ok(exam) => {
+                                if let Err(e) = recent::save(exam_path) {
+                                    errln!("{}Unable to remember the selected exam:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                                }
+                                let exam = exam.offer_union(exam_path, &exam_dir);
+                                let next_paths = exam_dir.iter().skip_while(|path| *path != exam_path).skip(1).cloned().collect();
+                                break (exam, next_paths);
+                            },
+                            Err(e) => errln!("{}{}{}", color(Role::Error), e, color(Role::Reset)),
+                        }
+                    },
+                    None => errln!("{}Unable to get list of exam files in chosen directory{}", color(Role::Error), color(Role::Reset)),
+                }
+            };
+            result
+        }
+
+        /// Helper function that loads the only exam file in the current directory and its `assets`
+        /// directory (counted together), so that it can be studied without any of the prompts of
+        /// the interactive flow; the session studies the exam's `default_count` questions, or
+        /// `QUICK_QUIZ_COUNT` of them.
+        ///
+        /// # Returns
+        ///
+        /// * `Option<Exam>` - the exam; `None` if there are no exam files or several of them, or
+        ///   the only one can't be loaded, in which case the interactive flow is used.
+        fn quick_quiz(cwd: &Path) -> Option<Exam> {
+            let mut paths = [cwd.to_path_buf(), cwd.join(ASSETS_DIR)]
+                .into_iter()
+                .filter_map(|dir| fs::read_dir(dir).ok())
+                .flatten()
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && Self::is_exam_file(path));
+            let (Some(path), None) = (paths.next(), paths.next()) else {
+                return None;
+            };
+            let mut exam = match Self::load_exam(&path, &mut Vec::new()) {
+                Ok(exam) => exam,
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            if let Err(e) = recent::save(&path) {
+                errln!("{}Unable to remember the selected exam:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+            }
+            let count = min(exam.default_count.filter(|count| *count > 0).unwrap_or(QUICK_QUIZ_COUNT), exam.questions.len());
+            exam.quick_count = Some(count);
+            outln!("{}Quick quiz: {} questions from {}, the only exam file here (see --help for more options){}",
+                color(Role::Info), count, path.display(), color(Role::Reset));
+            Some(exam)
+        }
+
+        /// Helper function that offers to resume studying the exam file that was last selected,
+        /// skipping the directory and exam selection. Returns `None` if there is no such exam, it
+        /// no longer exists or can't be loaded, or the user declines.
+        fn offer_resume() -> Option<Exam> {
+            let path = match recent::load() {
+                Ok(path) => path?,
+                Err(e) => {
+                    errln!("{}Unable to read the last exam studied:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            if !path.is_file() {
+                outln!("{}The last exam studied ({}) no longer exists{}", color(Role::Info), path.display(), color(Role::Reset));
+                return None;
+            }
+            let exam = match Self::load_exam(&path, &mut Vec::new()) {
+                Ok(exam) => exam,
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            let answer = Self::input_optional(&format!("Resume last exam: {}? (Y/n) ", exam.name));
+            (!answer.to_ascii_lowercase().starts_with('n')).then_some(exam)
+        }
+
+        /// Helper function that loads every listed exam file and merges them into a single exam:
+        /// the questions of each file follow those of the previous one, and duplicate questions are
+        /// only kept where they first appear. Files that can't be loaded are reported and left out.
+        /// Returns `None` if none of the files could be loaded.
+        fn combine_exams(exam_dir: &[PathBuf]) -> Option<Exam> {
+            let mut combined: Option<Exam> = None;
+            let (mut num_files, mut num_questions) = (0, 0);
+            for path in exam_dir.iter() {
+                let exam = match Self::load_exam(path, &mut Vec::new()) {
+                    Ok(exam) => exam,
+                    Err(e) => {
+                        errln!("{}Leaving out {}:\t{}{}", color(Role::Warning), path.display(), e, color(Role::Reset));
+                        continue;
+                    },
+                };
+                num_files += 1;
+                num_questions += exam.questions.len();
+                match combined.as_mut() {
+                    Some(combined) => {
+                        combined.questions.extend(exam.questions);
+                        for (term, synonyms) in exam.synonyms {
+                            combined.synonyms.entry(term).or_default().extend(synonyms);
+                        }
+                    },
+                    None => combined = Some(exam),
+                }
+            }
+            let Some(mut combined) = combined else {
+                errln!("{}None of the exam files could be loaded{}", color(Role::Error), color(Role::Reset));
+                return None;
+            };
+            // Settings that apply to a single exam don't carry over to the combination of them
+            let dir_name = exam_dir[0].parent().and_then(Path::file_name).map_or("directory".into(), |name| name.to_string_lossy());
+            combined.name = format!("All exams in {}", dir_name);
+            combined.source = None;
+            combined.simulation = None;
+            combined.instructions = None;
+            combined.normalize.clear();
+            combined.pools.clear();
+            combined.default_count = None;
+            combined.feedback = Feedback::default();
+            combined.preserve_order = false;
+            outln!("{}Combined {} exam files into {} questions ({} duplicates removed){}",
+                color(Role::Success), num_files, combined.questions.len(), num_questions - combined.questions.len(), color(Role::Reset));
+            Some(combined)
+        }
+
+        /// Helper function that picks one of the (non-empty) listed exam files at random, and tells
+        /// the user which one was picked.
+        fn pick_random_exam(exam_dir: &[PathBuf]) -> &PathBuf {
+            let exam_path = exam_dir.choose(&mut rand::thread_rng()).expect("the exam directory isn't empty");
+            let file_name = exam_path.file_name().map_or(exam_path.display().to_string(), |name| name.to_string_lossy().to_string());
+            outln!("{}Randomly selected: {}{}", color(Role::Success), file_name, color(Role::Reset));
+            exam_path
+        }
+
+        /// Helper function that looks for other exam files in the directory whose questions
+        /// overlap heavily with this exam's (e.g., an accidental copy), warns about each one, and
+        /// offers to study the union of their questions instead.
+        fn offer_union(mut self, path: &Path, exam_dir: &[PathBuf]) -> Exam {
+            let ids: HashSet<String> = self.questions.iter().map(Question::id).collect();
+            for other_path in exam_dir.iter().filter(|other| *other != path) {
+                // Files that can't be loaded will be reported if they are ever selected
+                let Ok(other) = Self::load_exam(other_path, &mut Vec::new()) else { continue };
+                let shared = other.questions.iter().filter(|question| ids.contains(&question.id())).count();
+                let smaller = min(ids.len(), other.questions.len());
+                if smaller == 0 || (shared as f64) < DUPLICATE_OVERLAP * smaller as f64 {
+                    continue;
+                }
+                let file_name = other_path.file_name().map_or(other_path.display().to_string(), |name| name.to_string_lossy().to_string());
+                outln!("{}{} shares {} of its {} questions with this exam; it may be a duplicate{}",
+                    color(Role::Warning), file_name, shared, other.questions.len(), color(Role::Reset));
+                if Self::input_optional("Study the questions of both files together (y/N)? ").eq_ignore_ascii_case("y") {
+                    self.questions.extend(other.questions);
+                    for (term, synonyms) in other.synonyms {
+                        self.synonyms.entry(term).or_default().extend(synonyms);
+                    }
+                    // The merged exam no longer corresponds to a single file that can be reloaded
+                    self.source = None;
+                }
+            }
+            self
+        }
+
+        /// Opens the exam file at `path` and parses its contents into an `Exam`, then recursively
+        /// merges in the questions of every exam file listed in its `include` field. Included paths
+        /// are resolved relative to the including file. The questions keep the order they were
+        /// authored in, with those of each included file following the including file's (which is
+        /// the order `preserve_order` asks them in); duplicate questions are only kept where they
+        /// first appear.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - the path of the exam file to load.
+        /// * `visiting` - the canonical paths of the files currently being loaded; used to detect
+        ///   include cycles.
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Exam, String>` - the merged `Exam`, or a message describing why it couldn't be
+        ///   loaded.
+        fn load_exam(path: &Path, visiting: &mut Vec<PathBuf>) -> Result<Exam, String> {
+            let canonical = path
+                .canonicalize()
+                .map_err(|e| format!("Unable to open exam {}:\t{}", path.display(), e))?;
+            if visiting.contains(&canonical) {
+                return Err(format!("Exam {} includes itself (directly or indirectly)", path.display()));
+            }
+            let mut exam = Self::read_exam_file(&canonical)?;
+
+            visiting.push(canonical);
+            let base_dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            // Explanation files are relative to the file that references them, which is no longer
+            // known once the questions of included files are merged in
+            exam.questions = exam.questions
+                .into_iter()
+                .map(|mut question| {
+                    if question.explanation.starts_with(EXPLANATION_FILE_PREFIX) {
+                        question.explanation_dir = Some(base_dir.clone());
+                    }
+                    question
+                })
+                .collect();
+            for include in exam.include.clone() {
+                let included = Self::load_exam(&base_dir.join(include), visiting)?;
+                exam.questions.extend(included.questions);
+                for (term, synonyms) in included.synonyms {
+                    exam.synonyms.entry(term).or_default().extend(synonyms);
+                }
+            }
+            visiting.pop();
+            exam.source = Some(path.to_path_buf());
+            Ok(exam)
+        }
+
+        /// Helper function that parses a single exam file (without resolving its `include` field);
+        /// gzip-compressed exams are transparently decompressed.
+        fn read_exam_file(path: &Path) -> Result<Exam, String> {
+            if path.to_string_lossy().ends_with(GIFT_SUFFIX) {
+                return Self::read_gift_file(path);
+            }
+            let exam_file = File::open(path)
+                .map_err(|e| format!("Unable to open exam {}:\t{}", path.display(), e))?;
+            let reader = BufReader::new(exam_file);
+            let parsed = if path.to_string_lossy().ends_with(GZIP_SUFFIX) {
+                serde_json::from_reader(GzDecoder::new(reader))
+            } else {
+                serde_json::from_reader(reader)
+            };
+            parsed.map_err(|e| match e.classify() {
+                Category::Io => format!("Unable to decompress {}; the archive may be corrupt:\t{}", path.display(), e),
+                _ => Self::parse_error_message(&path.display().to_string(), &e),
+            })
+        }
+
+        /// Helper function that imports a GIFT quiz file as an exam named after the file; questions
+        /// that can't be imported are reported and left out.
+        fn read_gift_file(path: &Path) -> Result<Exam, String> {
+            let text = fs::read_to_string(path)
+                .map_err(|e| format!("Unable to open exam {}:\t{}", path.display(), e))?;
+            let (imported, skipped) = gift::parse(&text);
+            for reason in skipped.iter() {
+                errln!("{}Skipped a question in {}: {}{}", color(Role::Warning), path.display(), reason, color(Role::Reset));
+            }
+            if imported.is_empty() {
+                return Err(format!("{} doesn't contain any questions that can be studied", path.display()));
+            }
+            let questions = imported
+                .into_iter()
+                .map(|question| Question {
+                    q_type: question.q_type.to_string(),
+                    prompt: question.prompt,
+                    choices: question.choices.into_iter().collect(),
+                    answer: question.answer,
+                    explanation: question.explanation,
+                    section: question.category,
+                    ..Question::default()
+                })
+                .collect();
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            Ok(Exam {
+                name: name.trim_end_matches(GIFT_SUFFIX).to_string(),
+                questions,
+                simulation: None,
+                include: Vec::new(),
+                synonyms: HashMap::new(),
+                explanation_files: RefCell::default(),
+                patterns: RefCell::default(),
+                source: None,
+                quick_count: None,
+                instructions: None,
+                normalize: Vec::new(),
+                pools: Vec::new(),
+                default_count: None,
+                feedback: Feedback::default(),
+                preserve_order: false,
+            })
+        }
+
+        /// Helper function that checks a session's number of questions against the `--min-count`,
+        /// warning when it falls short; `what` describes where the count came from (e.g.,
+        /// "available").
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - whether the session may go ahead, which it may when the count is short only
+        ///   if `--allow-short` was given.
+        fn meets_min_count(count: usize, what: &str, settings: &Settings) -> bool {
+            let Some(min_count) = settings.min_count.filter(|min_count| count < *min_count) else { return true };
+            if settings.allow_short {
+                errln!("{}Warning: only {} questions {}, fewer than the minimum of {}{}", color(Role::Warning), count, what, min_count, color(Role::Reset));
+                true
+            } else {
+                errln!("{}Only {} questions {}; at least {} are required (use --allow-short to study them anyway){}", color(Role::Error), count, what, min_count, color(Role::Reset));
+                false
+            }
+        }
+
+        /// Helper function that describes why an exam couldn't be parsed, distinguishing files that
+        /// aren't valid JSON from exams with a missing or mistyped field, along with where the
+        /// problem was found.
+        fn parse_error_message(source: &str, e: &serde_json::Error) -> String {
+            // The error's own message ends with the position, which is reported separately
+            let message = e.to_string();
+            let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+            let problem = match e.classify() {
+                Category::Data => "doesn't match the exam format",
+                _ => "isn't valid JSON",
+            };
+            format!("{} {} (line {}, column {}):\t{}", source, problem, e.line(), e.column(), message)
+        }
+
+        /// Helper function that writes the exam to the given path as pretty-printed JSON; the
+        /// file is gzip-compressed when its name ends with `.json.gz`.
+        fn save(&self, path: &Path) -> io::Result<()> {
+            let json = serde_json::to_string_pretty(self)?;
+            if path.to_string_lossy().ends_with(GZIP_SUFFIX) {
+                let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+                encoder.write_all(json.as_bytes())?;
+                encoder.finish()?;
+                Ok(())
+            } else {
+                fs::write(path, json)
+            }
+        }
+
+        /// Helper function that obtains the path to the directory where the user has stored their
+        /// exam files. The user can opt to use the `assets` directory, which is created as one of
+        /// the initial steps in the `Exam` constructor, or uses a different directory of the user's
+        /// choosing.
+        fn select_asset_directory(cwd: &Path) -> PathBuf {
+            loop {
+                match Self::input("\nSearch default directory for exam files (Y/n)? ").chars().next().unwrap_or('n') {
+                    'y' | 'Y' => break cwd.join(ASSETS_DIR),
+                    'n' | 'N' => {
+                        let user_dir = PathBuf::from(Self::input_confirm("Enter full path to exam directory: "));
+                        if user_dir.exists() && user_dir.is_dir() {
+                            break user_dir
+                        } else {
+                            errln!("{}Please enter a valid directory!{}", color(Role::Error), color(Role::Reset));
+                        }
+                    },
+                    _ => errln!("{}Please enter a valid option!{}", color(Role::Error), color(Role::Reset)),
+                }
+            }
+        }
+
+        /// Lists the exams in the given directory along with each exam's name and number of
+        /// questions, without studying any of them.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `false` if the directory couldn't be read.
+        pub fn list(dir: &Path) -> bool {
+            match Self::display_and_collect_available_exams(dir.to_path_buf(), true) {
+                Some(exams) if exams.is_empty() => {
+                    outln!("\tNone");
+                    true
+                },
+                Some(_) => true,
+                None => false,
+            }
+        }
+
+        /// Lists the exams that are available to study by the file extension ending in `json` (or
+        /// `json.gz` for gzip-compressed exams) at the directory provided. If the directory with the
+        /// exam files exist, this display the exams with a number prefix and return an `Option` with
+        /// the vector containing the file paths. With `details`, each file is also loaded to show
+        /// the exam's name and number of questions.
+        fn display_and_collect_available_exams(dir: PathBuf, details: bool) -> Option<Vec<PathBuf>> {
+            match fs::read_dir(&dir) {
+                Ok(entries) => {
+                    outln!("\nThe following compatible exam files were found:");
+                    let exams: Vec<PathBuf> = entries
+                        .filter(|e|
+                             e.as_ref().is_ok_and(|e|
+                                 e.path().is_file() && Self::is_exam_file(&e.path())
+                             )
+                        )
+                        .enumerate()
+                        .map(|(index, e)| {
+                            let path: PathBuf = e.expect("unreadable entries were filtered out").path();
+                            // Names that aren't valid UTF-8 are only displayed lossily; the path
+                            // itself is kept intact for opening the file
+                            let filename = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+                            if details {
+                                let summary = match Self::load_exam(&path, &mut Vec::new()) {
+                                    Ok(exam) => format!("{} ({} questions)", exam.name, exam.questions.len()),
+                                    Err(_) => "unable to read exam".to_string(),
+                                };
+                                outln!("\t{}{}.) {}{} - {}", color(Role::Info), index + 1, filename, color(Role::Reset), summary);
+                            } else {
+                                outln!("\t{}{}.) {}{}", color(Role::Info), index + 1, filename, color(Role::Reset));
+                            }
+                            path
+                        })
+                        .collect();
+                    Some(exams)
+                },
+                Err(e) => {
+                    errln!("{}{}{}", color(Role::Error), Self::directory_error_message(&e, &dir), color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Helper function that describes an error from creating or reading a directory; a
+        /// permissions problem gets a specific, actionable message since it's otherwise easily
+        /// mistaken for a missing or empty directory.
+        fn directory_error_message(e: &io::Error, dir: &Path) -> String {
+            match e.kind() {
+                ErrorKind::PermissionDenied => format!(
+                    "Permission denied accessing the directory {}; check that your user can read and write to it (e.g., 'ls -ld {}') or choose a different directory",
+                    dir.display(), dir.display()
+                ),
+                ErrorKind::NotFound => format!("The directory {} doesn't exist", dir.display()),
+                _ => format!("Unable to access the directory {}:\t{}", dir.display(), e),
+            }
+        }
+
+        /// Helper function that determines whether a file is a supported exam file by its name;
+        /// names that aren't valid UTF-8 are judged by their lossy conversion.
+        fn is_exam_file(path: &Path) -> bool {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .filter(|name| !DATA_FILES.contains(&name.as_ref()))
+                .is_some_and(|name| name.ends_with(".json") || name.ends_with(GZIP_SUFFIX) || name.ends_with(GIFT_SUFFIX))
+        }
+
+        /// Helper function for displaying a prompt that the user can respond to in-line with the
+        /// prompt.
+        fn input(prompt: &str) -> String {
+            let mut temp: String = String::new();
+            while temp.trim().is_empty() {
+                print!("{}", prompt);
+                stdout().flush().expect("Unable to flush stdout...");
+                stdin().read_line(&mut temp).expect("Unable to read from stdin");
+            }
+            transcribe(&format!("{}{}", prompt, temp.trim()));
+            temp.trim().to_string()
+        }
+
+        /// Helper function that continues a multi-line answer after its first line, reading lines
+        /// until one containing only a period (or the end of input). The lines are joined with
+        /// newlines, and blank lines are kept so that paragraphs stay apart.
+        fn input_paragraph(first_line: String) -> String {
+            if first_line == "." {
+                return String::new();
+            }
+            let mut lines = vec![first_line];
+            loop {
+                let mut line = String::new();
+                match stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {},
+                }
+                let line = line.trim_end();
+                transcribe(line);
+                if line.trim() == "." {
+                    break;
+                }
+                lines.push(line.to_string());
+            }
+            lines.join("\n").trim().to_string()
+        }
+
+        /// Helper function for collecting the user's answer to a question. Recognizes the `note`
+        /// command, which records a note about the current question to the notes file before
+        /// prompting for the answer again, and the `back` and `quit` commands, which interrupt the
+        /// question via `Err(SessionCommand::Back)` and `Err(SessionCommand::Quit)`. The `broken`
+        /// command reports the question as broken, then skips it via `Err(SessionCommand::Skip)`,
+        /// and `help` (or `?`) lists these commands before prompting again.
+        fn answer_input(&self, prompt: &str, question: &Question) -> Result<String, SessionCommand> {
+            loop {
+                let input = Self::input(prompt);
+                if input.eq_ignore_ascii_case("help") || input == "?" {
+                    Self::display_session_commands();
+                } else if input.eq_ignore_ascii_case("back") {
+                    return Err(SessionCommand::Back);
+                } else if input.eq_ignore_ascii_case("quit") {
+                    return Err(SessionCommand::Quit);
+                } else if input.eq_ignore_ascii_case("broken") {
+                    let note = Self::input_optional("Describe the problem (optional): ");
+                    match reports::add(&self.name, question, &note) {
+                        Ok(path) => outln!("{}Reported the question as broken in {}; skipping it{}", color(Role::Success), path.display(), color(Role::Reset)),
+                        Err(e) => errln!("{}Unable to save report:\t{}{}", color(Role::Error), e, color(Role::Reset)),
+                    }
+                    return Err(SessionCommand::Skip);
+                } else if input.eq_ignore_ascii_case("note") {
+                    let text = Self::input("Enter note: ");
+                    match notes::append(&question.id(), &text) {
+                        Ok(path) => outln!("{}Note saved to {}{}", color(Role::Success), path.display(), color(Role::Reset)),
+                        Err(e) => errln!("{}Unable to save note:\t{}{}", color(Role::Error), e, color(Role::Reset)),
+                    }
+                } else {
+                    return Ok(input);
+                }
+            }
+        }
+
+        /// Helper function that lists the commands recognized at any answer prompt.
+        fn display_session_commands() {
+            outln!("{}Commands available at the answer prompt:{}", color(Role::Info), color(Role::Reset));
+            for (command, description) in SESSION_COMMANDS.iter() {
+                outln!("{}\t{:<10}{}{}", color(Role::Hint), command, description, color(Role::Reset));
+            }
+        }
+
+        /// Helper function that prompts the user to enter info in-line with a prompt twice to
+        /// verify the user's input is accurate.
+        fn input_confirm(prompt: &str) -> String {
+            loop {
+                let in1: String = Self::input(prompt);
+                if Self::confirm_entry(&in1) {
+                    return in1;
+                }
+            }
+        }
+
+        /// Helper function that asks the user to enter the given entry a second time, returning
+        /// whether both entries match.
+        fn confirm_entry(entry: &str) -> bool {
+            let in2: String = Self::input("Confirm entry: ");
+            if entry.eq(&in2) {
+                true
+            } else {
+                errln!("{}Entries must match!{}", color(Role::Error), color(Role::Reset));
+                false
+            }
+        }
+
+        /// Method for studying questions from an exam in the `assets` directory. This will ask the
+        /// user how many questions they'd like to study. If the user enters a number of questions
+        /// that exceeds the number of questions in the exam JSON file, then the entire contents of
+        /// the exam file will be studied. After the study session has completed, a ratio of the
+        /// number of questions correctly answered to the number of questions studied will be
+        /// displayed, along with a breakdown by difficulty when questions specify one.
+        ///
+        /// # Arguments
+        ///
+        /// * `has_next_exam` - whether there is another exam the user can continue with instead of
+        ///   playing again.
+        ///
+        /// # Returns
+        ///
+        /// * `StudyOutcome` - the totals of every session played, and whether the user chose to
+        ///   continue with the next exam.
+        pub fn study(&self, settings: &Settings, has_next_exam: bool) -> StudyOutcome {
+            // Display the exam the user selected to study
+            outln!("\n\n{}Exam selected: {}{}", color(Role::Success), &self.name, color(Role::Reset));
+            outln!("{}Enter 'help' at any answer prompt to list the available commands{}", color(Role::Info), color(Role::Reset));
+            if let Some(instructions) = &self.instructions {
+                outln!();
+                word_wrap(instructions, terminal_width()).iter().for_each(|line| outln!("{}", line));
+                Self::input_optional("\nPress Enter to begin...");
+            }
+
+            // A practice set replays a saved sequence of questions instead of selecting them
+            let practice_set = match &settings.practice_set {
+                Some(name) => match self.load_practice_set(name) {
+                    Some(set) => Some(set),
+                    None => return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() },
+                },
+                None => None,
+            };
+            // Used for shuffling the questions and the choices of each question; the seed is kept
+            // so that the session can be saved as a practice set and reproduced later
+            let seed = practice_set.as_ref().map_or_else(rand::random, |set| set.seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            // Combine filters interactively when requested; the questions missed last time are one
+            // of the filters, so reviewing them isn't offered separately
+            let built_filters: Option<FilterSet> = (settings.filter_builder && practice_set.is_none()).then(|| self.build_filters());
+            // Offer to review only the questions missed in the previous session of this exam
+            let missed: Option<BTreeSet<String>> = if practice_set.is_none() && built_filters.is_none() { self.offer_missed_review() } else { None };
+
+            // Only study bookmarked questions when requested
+            let bookmarked: Option<BTreeSet<String>> = if settings.bookmarked {
+                match bookmarks::load() {
+                    Ok(mut all) => Some(all.remove(&self.name).unwrap_or_default()),
+                    Err(e) => {
+                        errln!("{}Unable to read bookmarks:\t{}{}", color(Role::Error), e, color(Role::Reset));
+                        return StudyOutcome::default();
+                    },
+                }
+            } else {
+                None
+            };
+
+            // Only study questions matching the requested difficulty, if any
+            let questions: Vec<&Question> = match &practice_set {
+                Some(set) => self.practice_set_questions(set),
+                None => {
+                    let mut questions: Vec<&Question> = self.session_questions()
+                        .into_iter()
+                        .filter(|q| settings.difficulty.is_none() || q.difficulty == settings.difficulty)
+                        .filter(|q| settings.section.is_none() || q.section == settings.section)
+                        .filter(|q| settings.reference.as_deref().is_none_or(|reference| q.cites(reference)))
+                        .filter(|q| bookmarked.as_ref().is_none_or(|ids| ids.contains(&q.id())))
+                        .filter(|q| missed.as_ref().is_none_or(|ids| ids.contains(&q.id())))
+                        .filter(|q| built_filters.as_ref().is_none_or(|filter_set| filter_set.matches(q)))
+                        .collect();
+                    if settings.new_only {
+                        Self::keep_unseen(&self.name, &mut questions);
+                    }
+                    if settings.skip_mastered
+                        && !Self::skip_mastered(&self.name, &mut questions, settings.mastery_streak.unwrap_or(DEFAULT_MASTERY_STREAK))
+                    {
+                        return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() };
+                    }
+                    // A separate generator, so that the choices are shuffled the same way whether
+                    // or not the questions were
+                    if !self.preserve_order {
+                        questions.shuffle(&mut StdRng::seed_from_u64(seed));
+                    }
+                    match settings.strategy {
+                        Strategy::Random => {},
+                        Strategy::Spaced => Self::order_by_staleness(&self.name, &mut questions),
+                        Strategy::Grouped => Self::keep_groups_together(&mut questions),
+                    }
+                    if let Some(count) = settings.warmup {
+                        Self::warm_up(&mut questions, count);
+                    }
+                    // Must-know questions come first so that every session includes them; the sort is
+                    // stable, so the rest keep their order
+                    questions.sort_by_key(|question| !question.always_include);
+                    // A review of missed questions, or a filtered set, isn't limited to the pools' draws
+                    if missed.is_none() && built_filters.is_none() {
+                        self.draw_from_pools(&mut questions);
+                    }
+                    questions
+                },
+            };
+            if questions.is_empty() {
+                errln!("{}There are no questions matching the selected difficulty, section, reference, or bookmarks{}", color(Role::Error), color(Role::Reset));
+                return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() };
+            }
+            if !Self::meets_min_count(questions.len(), "available", settings) {
+                return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() };
+            }
+            if settings.endless {
+                return self.study_endless(settings, questions, &mut rng);
+            }
+
+            // Ask the user for desired number of questions and save result; a practice set, a review
+            // of missed questions, the questions matching the built filters, and the questions drawn
+            // from the exam's pools are always studied in full, while the quick quiz has its count
+            let num_questions: usize = if practice_set.is_some() || missed.is_some() || built_filters.is_some() || !self.pools.is_empty() {
+                questions.len()
+            } else if let Some(count) = self.quick_count {
+                min(count, questions.len())
+            } else if let Some((min_count, max_count)) = settings.count_range {
+                // Picked separately from the session's generator so that a practice set saved from
+                // this session shuffles its choices the same way
+                let count = min(rand::thread_rng().gen_range(min_count..=max_count), questions.len());
+                outln!("Studying {} questions (picked between {} and {})", count, min_count, max_count);
+                if !Self::meets_min_count(count, "picked", settings) {
+                    return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() };
+                }
+                count
+            } else {
+                loop {
+                    // The author's recommended count (if any) is used when nothing is entered
+                    let entry = match self.default_count.filter(|count| *count > 0) {
+                        Some(count) => match Self::input_optional(&format!("How many questions would you like to review? [{}] ", count)) {
+                            entry if entry.is_empty() => count.to_string(),
+                            entry => entry,
+                        },
+                        None => Self::input("How many questions would you like to review? "),
+                    };
+                    match entry.parse::<usize>() {
+                        // Asking again when the count is too short, since there are enough questions
+                        Ok(num) if num > 0 => {
+                            if Self::meets_min_count(min(num, questions.len()), "requested", settings) {
+                                break min(num, questions.len());
+                            }
+                        },
+                        _ => errln!("{}Please enter a positive number!{}", color(Role::Error), color(Role::Reset)),
+                    }
+                }
+            };
+
+            let num_always_included = questions.iter().filter(|question| question.always_include).count();
+            if practice_set.is_none() && num_always_included > num_questions {
+                outln!("{}Only {} of the {} questions that are always included fit in this session{}",
+                    color(Role::Warning), num_questions, num_always_included, color(Role::Reset));
+            }
+            if settings.learn_then_test {
+                return self.learn_then_test(settings, questions.into_iter().take(num_questions).collect(), &mut rng, has_next_exam);
+            }
+
+            // Index-based iteration over the questions so that the user can go `back`; the result
+            // of each question is kept separately so that revisited questions can be re-scored
+            let mut questions: Vec<&Question> = questions.into_iter().take(num_questions).collect();
+            let mut num_questions = num_questions;
+            let mut results: Vec<Option<bool>> = vec![None; num_questions];
+            // Seconds taken to answer each question
+            let mut timings: Vec<f64> = vec![0.0; num_questions];
+            // Hints revealed before answering each question
+            let mut hints_used: Vec<usize> = vec![0; num_questions];
+            let mut flagger = AutoFlagger::new(&self.name, settings);
+            let attempt_log = AttemptLog::start(&self.name);
+            // References of every question that was shown, for the reading list at the end
+            let mut sources: BTreeSet<&str> = BTreeSet::new();
+            let mut index = 0;
+            while index < num_questions {
+                sources.extend(questions[index].refs.iter().map(|source| source.trim()).filter(|source| !source.is_empty()));
+                // Reversible questions are asked in either direction, picked at random
+                let reversed: Question;
+                let question = if questions[index].reversible && rng.gen_bool(0.5) {
+                    reversed = questions[index].reversed();
+                    &reversed
+                } else {
+                    questions[index]
+                };
+                let recalled: Question;
+                let question = if settings.recall && question.q_type != "ue" {
+                    recalled = question.recalled();
+                    &recalled
+                } else {
+                    question
+                };
+                let instantiated: Question;
+                let question = if question.is_templated() {
+                    instantiated = question.instantiated(&mut rng);
+                    &instantiated
+                } else {
+                    question
+                };
+                // Display the question prompt; compact output doesn't separate questions with a blank line
+                let separator = if settings.compact { "" } else { "\n" };
+                outln!("{}{}", separator, math::render(question.pick_prompt(&mut rng)));
+
+                let start = Instant::now();
+                let response = match self.ask_with_attempts(question, settings, &mut rng) {
+                    Ok(response) => response,
+                    Err(SessionCommand::Back) => {
+                        if index == 0 {
+                            errln!("{}There is no previous question to go back to!{}", color(Role::Error), color(Role::Reset));
+                        } else {
+                            // Un-score the previous question so that it can be answered again
+                            index -= 1;
+                            results[index] = None;
+                            outln!("{}Returning to the previous question...{}", color(Role::Info), color(Role::Reset));
+                        }
+                        continue;
+                    },
+                    Err(SessionCommand::Quit) => break,
+                    Err(SessionCommand::Skip) => {
+                        // Skipped questions are left unscored
+                        index += 1;
+                        Self::display_progress(index, num_questions);
+                        continue;
+                    },
+                };
+                let is_correct = response.is_correct;
+                attempt_log.record(question, &response.given, is_correct);
+                results[index] = Some(is_correct);
+                timings[index] = start.elapsed().as_secs_f64();
+                hints_used[index] = response.hints;
+                self.give_feedback(question, is_correct, Some(flagger.misses_after(question, is_correct)), settings);
+                flagger.observe(question, is_correct, timings[index]);
+
+                // Ask the follow-up question triggered by this answer, if any, right after this one
+                if let Some(followup) = self.followup(question, &response.given) {
+                    match questions.iter().position(|q| q.id() == followup.id()) {
+                        // Follow-ups that were already asked aren't asked again, which prevents loops
+                        Some(position) if position <= index => {},
+                        // Follow-ups later in the session are moved up; their results are still empty
+                        Some(position) => {
+                            let followup = questions.remove(position);
+                            questions.insert(index + 1, followup);
+                        },
+                        None => {
+                            questions.insert(index + 1, followup);
+                            results.insert(index + 1, None);
+                            timings.insert(index + 1, 0.0);
+                            hints_used.insert(index + 1, 0);
+                            num_questions += 1;
+                        },
+                    }
+                }
+
+                index += 1;
+                Self::display_progress(index, num_questions);
+
+                // End the session early once the user runs out of lives
+                if let Some(lives) = settings.lives {
+                    let num_wrong = results.iter().filter(|result| **result == Some(false)).count();
+                    if num_wrong >= lives {
+                        if settings.hide_score {
+                            outln!("\n{}Game over - you ran out of lives{}", color(Role::Error), color(Role::Reset));
+                        } else {
+                            outln!("\n{}Game over - you missed {}{}", color(Role::Error), num_wrong, color(Role::Reset));
+                            let num_right = results.iter().filter(|result| **result == Some(true)).count();
+                            outln!("You got {}/{} questions correct before running out of lives.", num_right, num_right + num_wrong);
+                        }
+                        break;
+                    } else if !is_correct {
+                        outln!("{}Lives remaining: {}{}", color(Role::Warning), lives - num_wrong, color(Role::Reset));
+                    }
+                }
+            }
+
+            // Counts the number of questions the user answered, and answered correctly; these
+            // differ from `num_questions` when the session ended early
+            let num_answered = results.iter().filter(|result| result.is_some()).count();
+            let num_correct = results.iter().filter(|result| **result == Some(true)).count();
+            let num_hints: usize = results.iter().zip(hints_used.iter()).filter(|(result, _)| result.is_some()).map(|(_, hints)| hints).sum();
+            // Tallies (correct, attempted) for each difficulty level that was studied
+            let mut by_difficulty: BTreeMap<Difficulty, (usize, usize)> = BTreeMap::new();
+            for (question, result) in questions.iter().zip(results.iter()) {
+                if let (Some(difficulty), Some(is_correct)) = (question.difficulty, result) {
+                    let tally = by_difficulty.entry(difficulty).or_default();
+                    tally.0 += usize::from(*is_correct);
+                    tally.1 += 1;
+                }
+            }
+            // Likewise for each section, to recommend a focused session on the weakest one
+            let mut by_section: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+            for (question, result) in questions.iter().zip(results.iter()) {
+                if let (Some(section), Some(is_correct)) = (question.section.as_deref(), result) {
+                    let tally = by_section.entry(section).or_default();
+                    tally.0 += usize::from(*is_correct);
+                    tally.1 += 1;
+                }
+            }
+
+            flagger.report();
+            // Offer to save the sequence of questions, unless it was already loaded from a set
+            if practice_set.is_none() && num_answered > 0 {
+                Self::offer_practice_set(&self.name, &questions, seed);
+            }
+            if num_answered > 0 {
+                let question_results = questions
+                    .iter()
+                    .zip(results.iter())
+                    .zip(timings.iter())
+                    .filter_map(|((question, result), seconds)| result.map(|correct| QuestionResult { id: question.id(), correct, seconds: *seconds, q_type: Some(question.q_type.clone()) }))
+                    .collect();
+                Self::record_session(&self.name, SessionMode::Practice, num_correct, num_answered, question_results);
+            }
+
+            // Ask whether or not to play again (nudging toward the daily goal if it isn't reached
+            // yet), or continue with the next exam
+            let goal_remaining = settings.daily_goal.and_then(Self::display_goal_progress);
+            let prompt = match (goal_remaining.is_some(), has_next_exam) {
+                (true, true) => "\n\nStart another session to reach your goal (Y/n), or [c]ontinue with the next exam? ",
+                (true, false) => "\n\nStart another session to reach your goal (Y/n)? ",
+                (false, true) => "\n\nPlay again (Y/n), or [c]ontinue with the next exam? ",
+                (false, false) => "\n\nPlay again (Y/n)? ",
+            };
+            match Self::input(prompt).chars().next().unwrap_or('n') {
+                'y' | 'Y' => {
+                    // Pick up any edits made to the exam file since it was loaded
+                    let mut outcome = match self.reload() {
+                        Some(reloaded) => reloaded.study(settings, has_next_exam),
+                        None => self.study(settings, has_next_exam),
+                    };
+                    outcome.correct += num_correct;
+                    outcome.answered += num_answered;
+                    outcome
+                },
+                choice => {
+                    if settings.hide_score {
+                        outln!("\nYour score is hidden, but it was saved to the study history.");
+                    } else {
+                        Self::display_score(num_correct, num_answered, num_hints, &by_difficulty, settings);
+                    }
+                    if !sources.is_empty() {
+                        outln!("\n{}Sources to review:{}", color(Role::Reference), color(Role::Reset));
+                        sources.iter().for_each(|source| outln!("\t- {}", source));
+                    }
+                    let next_exam = has_next_exam && choice.eq_ignore_ascii_case(&'c');
+                    if !next_exam {
+                        if let Some(mut outcome) = self.offer_focused_session(&by_section, settings, has_next_exam) {
+                            outcome.correct += num_correct;
+                            outcome.answered += num_answered;
+                            return outcome;
+                        }
+                        outln!("Great progress studying!");
+                    }
+                    StudyOutcome { correct: num_correct, answered: num_answered, next_exam }
+                }
+            }
+        }
+
+        /// Helper function that tells the user how close they are to their daily goal of answered
+        /// questions (across every exam studied today).
+        ///
+        /// # Returns
+        ///
+        /// * `Option<usize>` - how many more questions must be answered to reach the goal, or
+        ///   `None` if it was reached (or today's progress couldn't be read).
+        fn display_goal_progress(goal: usize) -> Option<usize> {
+            let answered = match history::answered_today() {
+                Ok(answered) => answered,
+                Err(e) => {
+                    errln!("{}Unable to read today's progress from the study history:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            match goal.checked_sub(answered).filter(|remaining| *remaining > 0) {
+                Some(remaining) => {
+                    outln!("\n{}{} more questions to reach today's goal of {}.{}", color(Role::Info), remaining, goal, color(Role::Reset));
+                    Some(remaining)
+                },
+                None => {
+                    outln!("\n{}You reached today's goal of {} questions ({} answered today)!{}", color(Role::Success), goal, answered, color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Helper function that displays the final score of a study session: the number and
+        /// percentage of questions answered correctly, along with the optional grade and adjusted
+        /// score (which `num_hints` counts toward under `--hint-penalty`), the estimated true
+        /// score, and the breakdown by difficulty.
+        fn display_score(num_correct: usize, num_answered: usize, num_hints: usize, by_difficulty: &BTreeMap<Difficulty, (usize, usize)>, settings: &Settings) {
+            if num_answered > 0 {
+                let percent = 100.0 * num_correct as f64 / num_answered as f64;
+                outln!("\nYou got {}/{} questions correct ({:.1}%).", num_correct, num_answered, percent);
+                if let Some(cutoffs) = settings.grade_cutoffs {
+                    Self::display_grade(percent, cutoffs);
+                }
+            } else {
+                outln!("\nYou got {}/{} questions correct.", num_correct, num_answered);
+            }
+            if settings.penalty.is_some() || settings.hint_penalty.is_some() {
+                let mut deductions: Vec<String> = Vec::new();
+                let mut deducted = 0.0;
+                if let Some(penalty) = settings.penalty {
+                    deducted += penalty * (num_answered - num_correct) as f64;
+                    deductions.push(format!("{} point(s) deducted per incorrect answer", penalty));
+                }
+                if let Some(hint_penalty) = settings.hint_penalty {
+                    deducted += hint_penalty * num_hints as f64;
+                    deductions.push(format!("{} per hint, with {} hint(s) used", hint_penalty, num_hints));
+                }
+                let score = Self::penalized_score(num_correct, deducted, settings.allow_negative);
+                outln!("Adjusted score: {:.2}/{} ({})", score, num_answered, deductions.join("; "));
+            }
+            if let Some((low, high)) = Self::wilson_interval(num_correct, num_answered) {
+                outln!("Estimated true score: roughly {:.0}-{:.0}% with {} question(s) answered (95% confidence).",
+                    100.0 * low, 100.0 * high, num_answered);
+            }
+            for (difficulty, (correct, attempted)) in by_difficulty.iter() {
+                outln!("\t{:?}: {}/{} ({:.0}%)", difficulty, correct, attempted, 100.0 * *correct as f64 / *attempted as f64);
+            }
+        }
+
+        /// Helper function that reads the exam file again, so that edits made while studying take
+        /// effect when playing again.
+        ///
+        /// # Returns
+        ///
+        /// * `Option<Exam>` - the reloaded exam; `None` if the exam wasn't loaded from a file, or if
+        ///   the file is no longer valid (with a warning), in which case this version is kept.
+        fn reload(&self) -> Option<Exam> {
+            let path = self.source.as_ref()?;
+            match Self::load_exam(path, &mut Vec::new()) {
+                // Playing a quick quiz again doesn't ask for the number of questions either
+                Ok(exam) => Some(Exam { quick_count: self.quick_count, ..exam }),
+                Err(e) => {
+                    errln!("{}Unable to reload the exam; keeping the previous version:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Helper function that recommends a focused session on the weakest section of a finished
+        /// session, when questions from more than one section were answered and one of them was
+        /// missed, and offers to start it right away.
+        ///
+        /// # Returns
+        ///
+        /// * `Option<StudyOutcome>` - the outcome of the focused session, if the user started one.
+        fn offer_focused_session(&self, by_section: &BTreeMap<&str, (usize, usize)>, settings: &Settings, has_next_exam: bool) -> Option<StudyOutcome> {
+            if by_section.len() < 2 {
+                return None;
+            }
+            let accuracy = |(correct, attempted): (usize, usize)| correct as f64 / attempted as f64;
+            let (section, tally) = by_section
+                .iter()
+                .min_by(|(_, a), (_, b)| accuracy(**a).total_cmp(&accuracy(**b)))?;
+            if tally.0 == tally.1 {
+                return None;
+            }
+            if settings.hide_score {
+                outln!("\n{}Your weakest area was '{}'; consider a focused session{}", color(Role::Warning), section, color(Role::Reset));
+            } else {
+                outln!("\n{}Your weakest area was '{}' ({:.0}%); consider a focused session{}",
+                    color(Role::Warning), section, 100.0 * accuracy(*tally), color(Role::Reset));
+            }
+            if !Self::input_optional("Start a session on this section now (y/N)? ").eq_ignore_ascii_case("y") {
+                return None;
+            }
+            let focused = Settings { section: Some(section.to_string()), ..settings.clone() };
+            Some(self.study(&focused, has_next_exam))
+        }
+
+        /// Helper function that moves the `count` easiest questions to the front, from easiest to
+        /// hardest, so that a session doesn't start with a hard question; the remaining questions
+        /// keep their order. Questions without a difficulty are treated as medium.
+        fn warm_up(questions: &mut Vec<&Question>, count: usize) {
+            let rank = |question: &&Question| question.difficulty.unwrap_or(Difficulty::Medium);
+            // The sort is stable, so questions of the same difficulty keep their shuffled order
+            let mut ranked: Vec<&Question> = questions.clone();
+            ranked.sort_by_key(rank);
+            ranked.truncate(count);
+            questions.retain(|question| !ranked.iter().any(|easy| std::ptr::eq(*easy, *question)));
+            questions.splice(0..0, ranked);
+        }
+
+        /// Interactive menu for combining filters (by section, difficulty, reference, group, and the
+        /// questions missed last time) with AND or OR before studying, showing how many questions
+        /// match after each change; entering nothing starts studying the matching questions.
+        fn build_filters(&self) -> FilterSet {
+            let mut filter_set = FilterSet::default();
+            loop {
+                let num_matching = self.questions.iter().filter(|question| filter_set.matches(question)).count();
+                let joiner = if filter_set.any { "OR" } else { "AND" };
+                outln!("\n{}Filters (combined with {}):{}", color(Role::Info), joiner, color(Role::Reset));
+                if filter_set.filters.is_empty() {
+                    outln!("\t(none)");
+                }
+                for (index, filter) in filter_set.filters.iter().enumerate() {
+                    outln!("\t{}.) {}", index + 1, filter.describe());
+                }
+                outln!("{}{} of {} questions match{}", color(Role::Success), num_matching, self.questions.len(), color(Role::Reset));
+                let prompt = "Add a filter by [s]ection, [d]ifficulty, [r]eference, [g]roup, or [m]issed last time; switch to [a]nd/[o]r;\n\
+                    [u]ndo the last filter; or press Enter to start: ";
+                let choice = Self::input_optional(prompt).to_ascii_lowercase();
+                match choice.as_str() {
+                    "" | "start" if num_matching == 0 => {
+                        errln!("{}No questions match; change the filters first!{}", color(Role::Error), color(Role::Reset));
+                    },
+                    "" | "start" => return filter_set,
+                    "s" | "section" => {
+                        let sections: BTreeSet<&String> = self.questions.iter().filter_map(|question| question.section.as_ref()).collect();
+                        if let Some(section) = Self::pick_filter_value("section", &sections.into_iter().collect::<Vec<_>>()) {
+                            filter_set.filters.push(Filter::Section(section.clone()));
+                        }
+                    },
+                    "d" | "difficulty" => {
+                        let difficulties: BTreeSet<Difficulty> = self.questions.iter().filter_map(|question| question.difficulty).collect();
+                        let difficulties: Vec<Difficulty> = difficulties.into_iter().collect();
+                        let labels: Vec<String> = difficulties.iter().map(|difficulty| format!("{:?}", difficulty)).collect();
+                        if let Some(label) = Self::pick_filter_value("difficulty", &labels.iter().collect::<Vec<_>>()) {
+                            let position = labels.iter().position(|other| other == label).expect("the label was listed");
+                            filter_set.filters.push(Filter::Difficulty(difficulties[position]));
+                        }
+                    },
+                    "r" | "reference" => {
+                        let reference = Self::input("Text the reference must contain (e.g., 'Chapter 7'): ");
+                        filter_set.filters.push(Filter::Reference(reference));
+                    },
+                    "g" | "group" => {
+                        let groups: BTreeSet<&String> = self.questions.iter().filter_map(|question| question.group.as_ref()).collect();
+                        if let Some(group) = Self::pick_filter_value("group", &groups.into_iter().collect::<Vec<_>>()) {
+                            filter_set.filters.push(Filter::Group(group.clone()));
+                        }
+                    },
+                    "m" | "missed" => match history::last_missed(&self.name) {
+                        Ok(missed) => filter_set.filters.push(Filter::Missed(missed.into_iter().collect())),
+                        Err(e) => errln!("{}Unable to read study history:\t{}{}", color(Role::Error), e, color(Role::Reset)),
+                    },
+                    "a" | "and" => filter_set.any = false,
+                    "o" | "or" => filter_set.any = true,
+                    "u" | "undo" => {
+                        if filter_set.filters.pop().is_none() {
+                            errln!("{}There are no filters to undo!{}", color(Role::Error), color(Role::Reset));
+                        }
+                    },
+                    _ => errln!("{}Please enter a valid option!{}", color(Role::Error), color(Role::Reset)),
+                }
+            }
+        }
+
+        /// Helper function that lists the values a filter can take and asks the user to pick one by
+        /// number; `None` if the exam has no such values, or nothing was picked.
+        fn pick_filter_value<'a>(what: &str, values: &[&'a String]) -> Option<&'a String> {
+            if values.is_empty() {
+                errln!("{}None of the questions have a {}{}", color(Role::Error), what, color(Role::Reset));
+                return None;
+            }
+            for (index, value) in values.iter().enumerate() {
+                outln!("\t{}.) {}", index + 1, value);
+            }
+            let selection = Self::input_optional(&format!("Enter the number of the {} (or nothing to cancel): ", what));
+            let value = selection.parse::<usize>().ok().and_then(|number| number.checked_sub(1)).and_then(|index| values.get(index));
+            if value.is_none() && !selection.is_empty() {
+                errln!("{}Please make a valid selection!{}", color(Role::Error), color(Role::Reset));
+            }
+            value.copied()
+        }
+
+        /// Helper function that offers to study only the questions of this exam that were missed in
+        /// its most recent session. Returns the IDs of those questions if the user accepts, or
+        /// `None` if none were missed (or the history can't be read) or the user declines.
+        fn offer_missed_review(&self) -> Option<BTreeSet<String>> {
+            let missed = match history::last_missed(&self.name) {
+                Ok(missed) => missed,
+                Err(e) => {
+                    errln!("{}Unable to read study history:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            // Questions that were removed from the exam since can't be reviewed
+            let missed: BTreeSet<String> = self.questions.iter().map(Question::id).filter(|id| missed.contains(id)).collect();
+            if missed.is_empty() {
+                return None;
+            }
+            let answer = Self::input_optional(&format!("Review the {} you missed last time? (Y/n) ", missed.len()));
+            (!answer.to_ascii_lowercase().starts_with('n')).then_some(missed)
+        }
+
+        /// Helper function that draws the session's questions from the exam's pools (if any): the
+        /// first `draw` questions of each pool's section are kept, in their current order, so that
+        /// the pools are mixed together. Questions outside every pool are left out, except for
+        /// must-know questions, which are always kept (and count toward their pool's draw).
+        fn draw_from_pools(&self, questions: &mut Vec<&Question>) {
+            if self.pools.is_empty() {
+                return;
+            }
+            let mut drawn: HashMap<&str, usize> = HashMap::new();
+            questions.retain(|question| {
+                let pool = self.pools.iter().find(|pool| question.section.as_deref() == Some(pool.section.as_str()));
+                let in_draw = match pool {
+                    Some(pool) => {
+                        let count = drawn.entry(&pool.section).or_default();
+                        *count += 1;
+                        *count <= pool.draw
+                    },
+                    None => false,
+                };
+                in_draw || question.always_include
+            });
+            for pool in self.pools.iter() {
+                let available = drawn.get(pool.section.as_str()).copied().unwrap_or_default();
+                if available < pool.draw {
+                    outln!("{}Pool '{}' only has {} of its {} questions available{}", color(Role::Warning), pool.section, available, pool.draw, color(Role::Reset));
+                }
+            }
+        }
+
+        /// Helper function that removes the questions that were answered in previous sessions, so
+        /// that a large exam can be worked through without repeats. Every question is kept (with a
+        /// message) when all of them have been seen, or when the history can't be read.
+        fn keep_unseen(exam: &str, questions: &mut Vec<&Question>) {
+            let seen = match history::last_seen(exam) {
+                Ok(last_seen) => last_seen,
+                Err(e) => {
+                    errln!("{}Unable to read study history; including every question:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    return;
+                },
+            };
+            if questions.iter().all(|question| seen.contains_key(&question.id())) {
+                outln!("{}You have seen every question already; including every question{}", color(Role::Info), color(Role::Reset));
+                return;
+            }
+            questions.retain(|question| !seen.contains_key(&question.id()));
+        }
+
+        /// Helper function that removes the questions that were answered correctly at least
+        /// `streak` times in a row in previous sessions (their most recent answers). When every
+        /// question is mastered, the user can choose to study them anyway; every question is also
+        /// kept (with a message) when the history can't be read.
+        ///
+        /// # Returns
+        ///
+        /// * `bool` - `false` if every question is mastered and the user chose not to study them.
+        fn skip_mastered(exam: &str, questions: &mut Vec<&Question>, streak: usize) -> bool {
+            let streaks = match history::streaks(exam) {
+                Ok(streaks) => streaks,
+                Err(e) => {
+                    errln!("{}Unable to read study history; including every question:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    return true;
+                },
+            };
+            let is_mastered = |question: &&Question| streaks.get(&question.id()).is_some_and(|correct| *correct >= streak);
+            if !questions.is_empty() && questions.iter().all(is_mastered) {
+                outln!("{}Congratulations, you have mastered all {} questions ({} correct answers in a row each)!{}",
+                    color(Role::Success), questions.len(), streak, color(Role::Reset));
+                return Self::input_optional("Study them anyway (y/N)? ").eq_ignore_ascii_case("y");
+            }
+            let num_questions = questions.len();
+            questions.retain(|question| !is_mastered(question));
+            if questions.len() < num_questions {
+                outln!("{}Skipping {} mastered question(s){}", color(Role::Info), num_questions - questions.len(), color(Role::Reset));
+            }
+            true
+        }
+
+        /// Helper function that keeps the questions of each `group` together, in order of their IDs,
+        /// while the groups and ungrouped questions stay in their shuffled order: each group takes
+        /// the place of its first question.
+        fn keep_groups_together<'a>(questions: &mut Vec<&'a Question>) {
+            let mut groups: HashMap<&'a str, Vec<&'a Question>> = HashMap::new();
+            for &question in questions.iter() {
+                if let Some(group) = question.group.as_deref() {
+                    groups.entry(group).or_default().push(question);
+                }
+            }
+            let mut grouped: Vec<&'a Question> = Vec::with_capacity(questions.len());
+            for &question in questions.iter() {
+                match question.group.as_deref() {
+                    Some(group) => {
+                        // The rest of the group was already placed along with its first question
+                        if let Some(mut members) = groups.remove(group) {
+                            members.sort_by_key(|member| member.id());
+                            grouped.extend(members);
+                        }
+                    },
+                    None => grouped.push(question),
+                }
+            }
+            *questions = grouped;
+        }
+
+        /// Helper function that orders the questions from least to most recently answered, so that
+        /// taking the first few favors questions that haven't been seen in a while; questions that
+        /// were never answered come first. The sort is stable, so ties keep their shuffled order.
+        fn order_by_staleness(exam: &str, questions: &mut [&Question]) {
+            match history::last_seen(exam) {
+                // `None` (never answered) sorts before any time
+                Ok(last_seen) => questions.sort_by_key(|question| last_seen.get(&question.id()).copied()),
+                Err(e) => errln!("{}Unable to read study history; questions are picked at random:\t{}{}",
+                    color(Role::Warning), e, color(Role::Reset)),
+            }
+        }
+
+        /// Helper function that loads the named practice set for this exam, printing the reason
+        /// when it can't be studied.
+        fn load_practice_set(&self, name: &str) -> Option<PracticeSet> {
+            let set = match practice_sets::load() {
+                Ok(mut sets) => sets.remove(name),
+                Err(e) => {
+                    errln!("{}Unable to read practice sets:\t{}{}", color(Role::Error), e, color(Role::Reset));
+                    return None;
+                },
+            };
+            match set {
+                Some(set) if set.exam == self.name => Some(set),
+                Some(set) => {
+                    errln!("{}Practice set '{}' was saved for the exam '{}'{}", color(Role::Error), name, set.exam, color(Role::Reset));
+                    None
+                },
+                None => {
+                    errln!("{}There is no practice set named '{}'{}", color(Role::Error), name, color(Role::Reset));
+                    None
+                },
+            }
+        }
+
+        /// Helper function that looks up the questions of a practice set, in the saved order;
+        /// questions that have since been removed from the exam are skipped with a warning.
+        fn practice_set_questions(&self, set: &PracticeSet) -> Vec<&Question> {
+            let by_id: HashMap<String, &Question> = self.questions.iter().map(|q| (q.id(), q)).collect();
+            let questions: Vec<&Question> = set.questions.iter().filter_map(|id| by_id.get(id).copied()).collect();
+            if questions.len() < set.questions.len() {
+                errln!("{}{} question(s) of the practice set are no longer in the exam{}",
+                    color(Role::Warning), set.questions.len() - questions.len(), color(Role::Reset));
+            }
+            questions
+        }
+
+        /// Helper function that asks whether to save the session's questions, in order, as a named
+        /// practice set that can be studied again with `--set`.
+        fn offer_practice_set(exam: &str, questions: &[&Question], seed: u64) {
+            let name = Self::input_optional("\nSave these questions as a practice set? Enter a name, or leave blank to skip: ");
+            if name.is_empty() {
+                return;
+            }
+            let set = PracticeSet { exam: exam.to_string(), seed, questions: questions.iter().map(|q| q.id()).collect() };
+            match practice_sets::save(&name, set) {
+                Ok(path) => outln!("{}Saved practice set '{}' to {}; study it again with '--set {}'{}",
+                    color(Role::Info), name, path.display(), name, color(Role::Reset)),
+                Err(e) => errln!("{}Unable to save the practice set:\t{}{}", color(Role::Error), e, color(Role::Reset)),
+            }
+        }
+
+        /// Runs an endless study session: the questions are reshuffled (unless the exam preserves
+        /// their authored order) and asked again each time the whole set has been answered, until
+        /// the user enters `quit`. Going `back` and `lives` aren't supported since there's no fixed
+        /// sequence or end to the session.
+        fn study_endless(&self, settings: &Settings, mut questions: Vec<&Question>, rng: &mut StdRng) -> StudyOutcome {
+            outln!("{}Endless mode: enter 'quit' at any answer prompt to stop{}", color(Role::Info), color(Role::Reset));
+            // Lifetime counters for the whole session, across every round
+            let mut num_answered = 0;
+            let mut num_correct = 0;
+            let mut num_rounds = 0;
+            let mut question_results: Vec<QuestionResult> = Vec::new();
+            let mut flagger = AutoFlagger::new(&self.name, settings);
+            let attempt_log = AttemptLog::start(&self.name);
+            'session: loop {
+                if !self.preserve_order {
+                    questions.shuffle(rng);
+                }
+                'round: for question in questions.iter() {
+                    let recalled: Question;
+                    let question = if settings.recall && question.q_type != "ue" {
+                        recalled = question.recalled();
+                        &recalled
+                    } else {
+                        question
+                    };
+                    let instantiated: Question;
+                    let question = if question.is_templated() {
+                        instantiated = question.instantiated(rng);
+                        &instantiated
+                    } else {
+                        question
+                    };
+                    if settings.hide_score {
+                        outln!("\n{}[Round {}]{}", color(Role::Prompt), num_rounds + 1, color(Role::Reset));
+                    } else {
+                        outln!("\n{}[Round {} - {}/{} correct so far]{}", color(Role::Prompt), num_rounds + 1, num_correct, num_answered, color(Role::Reset));
+                    }
+                    outln!("{}", math::render(question.pick_prompt(rng)));
+                    let start = Instant::now();
+                    let response = loop {
+                        match self.ask_with_attempts(question, settings, rng) {
+                            Ok(response) => break response,
+                            Err(SessionCommand::Back) => {
+                                errln!("{}Going back isn't available in endless mode{}", color(Role::Error), color(Role::Reset));
+                            },
+                            Err(SessionCommand::Quit) => break 'session,
+                            Err(SessionCommand::Skip) => continue 'round,
+                        }
+                    };
+                    let is_correct = response.is_correct;
+                    attempt_log.record(question, &response.given, is_correct);
+                    let seconds = start.elapsed().as_secs_f64();
+                    num_answered += 1;
+                    num_correct += usize::from(is_correct);
+                    question_results.push(QuestionResult { id: question.id(), correct: is_correct, seconds, q_type: Some(question.q_type.clone()) });
+                    self.give_feedback(question, is_correct, Some(flagger.misses_after(question, is_correct)), settings);
+                    flagger.observe(question, is_correct, seconds);
+                }
+                num_rounds += 1;
+                outln!("\n{}Completed round {}; reshuffling the questions...{}", color(Role::Success), num_rounds, color(Role::Reset));
+            }
+
+            flagger.report();
+            if num_answered > 0 {
+                Self::record_session(&self.name, SessionMode::Practice, num_correct, num_answered, question_results);
+            }
+            if settings.hide_score {
+                outln!("\nYou completed {} round(s); your score is hidden, but it was saved to the study history.", num_rounds);
+            } else {
+                outln!("\nYou got {}/{} questions correct over {} complete round(s).", num_correct, num_answered, num_rounds);
+            }
+            if let Some((low, high)) = Self::wilson_interval(num_correct, num_answered) {
+                outln!("Estimated true score: roughly {:.0}-{:.0}% with {} question(s) answered (95% confidence).",
+                    100.0 * low, 100.0 * high, num_answered);
+            }
+            outln!("Great progress studying!");
+            StudyOutcome { correct: num_correct, answered: num_answered, next_exam: false }
+        }
+
+        /// Runs a two-phase session over the given questions: a learning phase that gives feedback
+        /// and explanations after each answer without scoring it, followed by a test phase that asks
+        /// the questions that were answered while learning again (reshuffled, unless the exam
+        /// preserves their authored order) without any feedback. Only the test phase is scored and
+        /// saved to the study history, as a `learn` session; both phases' results are reported at
+        /// the end. `quit` during the learning phase moves on to the test, and going `back` isn't
+        /// supported. Like `study`, it then offers to play again or continue with the next exam.
+        fn learn_then_test(&self, settings: &Settings, questions: Vec<&Question>, rng: &mut StdRng, has_next_exam: bool) -> StudyOutcome {
+            let attempt_log = AttemptLog::start(&self.name);
+            outln!("{}Learning phase: {} questions with feedback that doesn't count toward your score; enter 'quit' to move on to the test of the questions learned so far{}",
+                color(Role::Info), questions.len(), color(Role::Reset));
+            let (mut learn_correct, mut learn_answered) = (0, 0);
+            // The questions answered while learning, which are the ones that get tested
+            let mut learned: Vec<&Question> = Vec::new();
+            for (index, question) in questions.iter().enumerate() {
+                outln!("\n{}[Learning {}/{}]{}", color(Role::Prompt), index + 1, questions.len(), color(Role::Reset));
+                let response = match self.ask_phase_question(question, settings, rng, false) {
+                    Ok((asked, response)) => {
+                        attempt_log.record(&asked, &response.given, response.is_correct);
+                        self.give_feedback(&asked, response.is_correct, None, settings);
+                        response
+                    },
+                    Err(SessionCommand::Quit) => break,
+                    Err(_) => continue,
+                };
+                learn_answered += 1;
+                learn_correct += usize::from(response.is_correct);
+                learned.push(question);
+            }
+            if learned.is_empty() {
+                outln!("\nNo questions were learned, so there's nothing to test.");
+                return StudyOutcome { next_exam: has_next_exam, ..StudyOutcome::default() };
+            }
+
+            Self::input_optional("\nPress Enter to start the test phase...");
+            outln!("{}Test phase: the questions you learned ({}), without feedback; only these answers are scored{}",
+                color(Role::Info), learned.len(), color(Role::Reset));
+            if !self.preserve_order {
+                learned.shuffle(rng);
+            }
+            let (mut test_correct, mut test_answered, mut test_hints) = (0, 0, 0);
+            let mut question_results: Vec<QuestionResult> = Vec::new();
+            // Questions missed in the test, as they were asked, revealed once it's over
+            let mut missed: Vec<Question> = Vec::new();
+            for (index, question) in learned.iter().enumerate() {
+                outln!("\n{}[Test {}/{}]{}", color(Role::Prompt), index + 1, learned.len(), color(Role::Reset));
+                let start = Instant::now();
+                let (asked, response) = match self.ask_phase_question(question, settings, rng, true) {
+                    Ok(answered) => answered,
+                    Err(SessionCommand::Quit) => break,
+                    Err(_) => continue,
+                };
+                attempt_log.record(&asked, &response.given, response.is_correct);
+                test_answered += 1;
+                test_correct += usize::from(response.is_correct);
+                test_hints += response.hints;
+                // Tallied as written, even if it was asked recalled or with freshly picked numbers
+                question_results.push(QuestionResult {
+                    id: question.id(),
+                    correct: response.is_correct,
+                    seconds: start.elapsed().as_secs_f64(),
+                    q_type: Some(question.q_type.clone()),
+                });
+                if !response.is_correct {
+                    missed.push(asked.into_owned());
+                }
+            }
+
+            if test_answered > 0 {
+                Self::record_session(&self.name, SessionMode::Learn, test_correct, test_answered, question_results);
+            }
+            if settings.hide_score {
+                outln!("\nYour scores are hidden, but the test phase was saved to the study history.");
+            } else {
+                let percent = if learn_answered > 0 { 100.0 * learn_correct as f64 / learn_answered as f64 } else { 0.0 };
+                outln!("\nLearning phase: {}/{} questions correct ({:.1}%); only the test phase below is scored.", learn_correct, learn_answered, percent);
+                Self::display_score(test_correct, test_answered, test_hints, &BTreeMap::new(), settings);
+            }
+            if !missed.is_empty() {
+                outln!("\n{}Missed in the test:{}", color(Role::Error), color(Role::Reset));
+                for question in missed.iter() {
+                    outln!("\t- {}", math::render(&question.prompt));
+                    Self::reveal_answer(question, settings);
+                }
+            }
+
+            let prompt = if has_next_exam { "\n\nPlay again (Y/n), or [c]ontinue with the next exam? " } else { "\n\nPlay again (Y/n)? " };
+            match Self::input(prompt).chars().next().unwrap_or('n') {
+                'y' | 'Y' => {
+                    // Pick up any edits made to the exam file since it was loaded
+                    let mut outcome = match self.reload() {
+                        Some(reloaded) => reloaded.study(settings, has_next_exam),
+                        None => self.study(settings, has_next_exam),
+                    };
+                    outcome.correct += test_correct;
+                    outcome.answered += test_answered;
+                    outcome
+                },
+                choice => {
+                    let next_exam = has_next_exam && choice.eq_ignore_ascii_case(&'c');
+                    if !next_exam {
+                        outln!("Great progress studying!");
+                    }
+                    StudyOutcome { correct: test_correct, answered: test_answered, next_exam }
+                },
+            }
+        }
+
+        /// Helper function that asks a question during either phase of `learn_then_test`, the same
+        /// way in both: recalled under `--recall`, and with freshly picked numbers if it's templated.
+        /// While learning, `--attempts` tries (and `--attempt-hints`) are given; a `scored` question
+        /// in the test phase gets a single attempt, since retrying would give away that the answer
+        /// was wrong. `back` isn't supported, so it's reported and the question is asked again.
+        ///
+        /// # Returns
+        ///
+        /// * `Result<(Cow<Question>, Response), SessionCommand>` - the question as it was asked,
+        ///   and the response to it; or `Skip` or `Quit` if the user entered that command.
+        fn ask_phase_question<'a>(&self, question: &'a Question, settings: &Settings, rng: &mut StdRng, scored: bool) -> Result<(Cow<'a, Question>, Response), SessionCommand> {
+            let mut asked = Cow::Borrowed(question);
+            if settings.recall && asked.q_type != "ue" {
+                asked = Cow::Owned(asked.recalled());
+            }
+            if asked.is_templated() {
+                asked = Cow::Owned(asked.instantiated(rng));
+            }
+            outln!("{}", math::render(asked.pick_prompt(rng)));
+            loop {
+                let response = if scored { self.ask(&asked, settings, rng) } else { self.ask_with_attempts(&asked, settings, rng) };
+                match response {
+                    Ok(response) => return Ok((asked, response)),
+                    Err(SessionCommand::Back) => {
+                        errln!("{}Going back isn't available in a learn-then-test session{}", color(Role::Error), color(Role::Reset));
+                    },
+                    Err(command) => return Err(command),
+                }
+            }
+        }
+
+        /// Helper function that prints the phrase for a correct or incorrect answer, revealing the
+        /// correct answers after an incorrect one
+        fn print_verdict(&self, question: &Question, is_correct: bool, settings: &Settings) {
+            if is_correct {
+                outln!("{}{}{}", color(Role::Success), self.feedback_phrase(true, settings), color(Role::Reset));
+            } else {
+                outln!("{}{}{}", color(Role::Error), self.feedback_phrase(false, settings), color(Role::Reset));
+                Self::reveal_answer(question, settings);
+            }
+        }
+
+        /// Helper function that picks the phrase shown after an answer: a random one of the
+        /// `--praise`/`--consolation` phrases if any were given, otherwise of the exam's `feedback`
+        /// phrases, otherwise "Correct!" or "Incorrect..."
+        fn feedback_phrase<'a>(&'a self, is_correct: bool, settings: &'a Settings) -> &'a str {
+            let (configured, authored, default) = if is_correct {
+                (&settings.praise, &self.feedback.correct, "Correct!")
+            } else {
+                (&settings.consolation, &self.feedback.incorrect, "Incorrect...")
+            };
+            let phrases = if configured.is_empty() { authored } else { configured };
+            phrases.choose(&mut rand::thread_rng()).map_or(default, String::as_str)
+        }
+
+        /// Helper function that tells the user whether their answer was correct, followed by the
+        /// question's explanation and references; pauses briefly so that each can be read (or for
+        /// the `--correct-delay`/`--incorrect-delay` configured for the outcome, if any). When
+        /// the number of times the question has been missed is known, the explanation adapts to
+        /// it: a question that keeps being missed gets a boxed explanation, while one that has
+        /// never been missed only gets the first sentence.
+        fn give_feedback(&self, question: &Question, is_correct: bool, misses: Option<usize>, settings: &Settings) {
+            self.print_verdict(question, is_correct, settings);
+            if settings.bell {
+                // One ring for a correct answer, two for an incorrect answer
+                Self::ring_bell(if is_correct { 1 } else { 2 });
+            }
+            // A delay configured for this outcome replaces the default pauses, and is taken once the
+            // explanation and references have been printed
+            let delay = if is_correct { settings.correct_delay } else { settings.incorrect_delay };
+            // Sleep for a bit so that the user can see the result before adding extra text; compact
+            // output is meant for fast drilling, so it doesn't pause
+            if !settings.compact && delay.is_none() {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+
+            // Have the user explain the answer in their own words before seeing the explanation
+            let explanation = self.explanation(question);
+            if settings.teach_back && !explanation.is_empty() {
+                Self::teach_back(&explanation);
+            }
+
+            // Compact output drops the labels, and puts the references on a single line (if any)
+            if settings.compact {
+                if !explanation.is_empty() {
+                    outln!("{}{}{}", color(Role::Explanation), math::render(&explanation), color(Role::Reset));
+                }
+                if !question.refs.is_empty() {
+                    outln!("{}{}{}", color(Role::Reference), question.refs.join("; "), color(Role::Reset));
+                }
+                if let Some(delay) = delay {
+                    std::thread::sleep(std::time::Duration::from_millis(delay));
+                }
+                return;
+            }
+
+            // Only print the explanation if one is provided; self-explanatory questions don't need explanation
+            if !explanation.is_empty() {
+                match misses {
+                    Some(misses) if misses >= PROMINENT_EXPLANATION_MISSES => Self::display_boxed_explanation(&explanation, misses),
+                    Some(0) => outln!("{}Explanation: {}{}", color(Role::Explanation), math::render(Self::first_sentence(&explanation)), color(Role::Reset)),
+                    _ => outln!("{}Explanation: {}{}", color(Role::Explanation), math::render(&explanation), color(Role::Reset)),
+                }
+            }
+            // Always print reference(s)
+            outln!("{}Reference(s):\n\t{}{}", color(Role::Reference), question.refs.join("\n\t"), color(Role::Reset));
+
+            // Sleep for a sec so that the user can see explanation & references
+            std::thread::sleep(delay.map_or(std::time::Duration::from_secs(1), std::time::Duration::from_millis));
+        }
+
+        /// Helper function that draws a box around an explanation, titled with how many times the
+        /// question has been missed, so that it stands out.
+        fn display_boxed_explanation(explanation: &str, misses: usize) {
+            let width = min(terminal_width(), 80).saturating_sub(4).max(20);
+            let lines = word_wrap(&math::render(explanation), width);
+            let title = format!(" Missed {} times - review this carefully ", misses);
+            let rule = "─".repeat((width + 2).saturating_sub(title.chars().count()));
+            outln!("{}┌{}{}┐{}", color(Role::Warning), title, rule, color(Role::Reset));
+            for line in lines.iter() {
+                let padding = " ".repeat(width.saturating_sub(line.chars().count()));
+                outln!("{}│ {}{}{}{} │{}", color(Role::Warning), color(Role::Explanation), line, padding, color(Role::Warning), color(Role::Reset));
+            }
+            outln!("{}└{}┘{}", color(Role::Warning), "─".repeat(width + 2), color(Role::Reset));
+        }
+
+        /// Helper function that shortens text to its first sentence (ending with `.`, `!`, or `?`
+        /// followed by whitespace); text with a single sentence is returned whole.
+        fn first_sentence(text: &str) -> &str {
+            text.char_indices()
+                .find(|(index, c)| matches!(c, '.' | '!' | '?') && text[index + 1..].starts_with(char::is_whitespace))
+                .map_or(text, |(index, _)| &text[..=index])
+        }
+
+        /// Helper function that reads the explanation of a question. An explanation of the form
+        /// `@file:<path>` is read from that file, which is only read once per session; when the
+        /// file can't be read, a warning is printed and the explanation is used as written.
+        fn explanation<'a>(&self, question: &'a Question) -> Cow<'a, str> {
+            let Some(file) = question.explanation.strip_prefix(EXPLANATION_FILE_PREFIX) else {
+                return Cow::Borrowed(&question.explanation);
+            };
+            let path = match &question.explanation_dir {
+                Some(dir) => dir.join(file.trim()),
+                None => PathBuf::from(file.trim()),
+            };
+            let mut files = self.explanation_files.borrow_mut();
+            let contents = files.entry(path).or_insert_with_key(|path| match fs::read_to_string(path) {
+                Ok(contents) => Some(contents.trim_end().to_string()),
+                Err(e) => {
+                    errln!("{}Unable to read the explanation file {}:\t{}{}", color(Role::Warning), path.display(), e, color(Role::Reset));
+                    None
+                },
+            });
+            match contents {
+                Some(contents) => Cow::Owned(contents.clone()),
+                None => Cow::Borrowed(&question.explanation),
+            }
+        }
+
+        /// Helper function that asks the user to summarize why the answer is correct, then reports
+        /// how many of the key terms from the question's explanation the summary covered.
+        fn teach_back(explanation: &str) {
+            let key_terms = Self::key_terms(explanation);
+            if key_terms.is_empty() {
+                return;
+            }
+            let summary = Self::key_terms(&Self::input("Explain why the answer is correct: "));
+            let missed: Vec<&str> = key_terms.difference(&summary).map(String::as_str).collect();
+            let num_covered = key_terms.len() - missed.len();
+            outln!("{}You covered {}/{} key terms{}", color(Role::Info), num_covered, key_terms.len(), color(Role::Reset));
+            if !missed.is_empty() {
+                outln!("{}Missed: {}{}", color(Role::Hint), missed.join(", "), color(Role::Reset));
+            }
+        }
+
+        /// Helper function that extracts the key terms of a text: its distinct words (lowercased)
+        /// that are at least `MIN_KEY_TERM_LEN` characters long and aren't common filler words.
+        fn key_terms(text: &str) -> BTreeSet<String> {
+            text.split(|c: char| !c.is_alphanumeric())
+                .map(str::to_lowercase)
+                .filter(|word| word.chars().count() >= MIN_KEY_TERM_LEN && !STOP_WORDS.contains(&word.as_str()))
+                .collect()
+        }
+
+        /// Runs a realistic mock exam: questions (unless the exam preserves their authored order) and
+        /// choices are shuffled, the question count and overall time limit come from the exam's
+        /// `simulation` metadata, and no feedback is given until the end, where the score is
+        /// compared against the passing threshold and broken down by section.
+        pub fn simulate(&self, settings: &Settings) {
+            let simulation = self.simulation.as_ref();
+            let num_questions = simulation
+                .and_then(|sim| sim.question_count)
+                .map_or(self.questions.len(), |count| min(count, self.questions.len()));
+            let time_limit_minutes = simulation.and_then(|sim| sim.time_limit_minutes).unwrap_or(DEFAULT_TIME_LIMIT_MINUTES);
+            let time_limit = Duration::from_secs(60 * time_limit_minutes);
+            let pass_percent = simulation.and_then(|sim| sim.pass_percent).unwrap_or(DEFAULT_PASS_PERCENT);
+
+            outln!("\n\n{}Exam simulation: {}{}", color(Role::Success), &self.name, color(Role::Reset));
+            outln!("{} questions; {} minute time limit; {:.0}% required to pass", num_questions, time_limit_minutes, pass_percent);
+
+            let mut rng = StdRng::from_entropy();
+            let mut questions: Vec<&Question> = self.questions.iter().collect();
+            if !self.preserve_order {
+                questions.shuffle(&mut rng);
+            }
+
+            let mut num_correct = 0;
+            // Questions reported as broken aren't scored
+            let mut num_scored = num_questions;
+            let mut question_results: Vec<QuestionResult> = Vec::new();
+            // Tallies (correct, attempted) for each section that was answered
+            let mut by_section: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+            let attempt_log = AttemptLog::start(&self.name);
+            let start = Instant::now();
+            for (index, question) in questions.into_iter().take(num_questions).enumerate() {
+                let remaining = time_limit.saturating_sub(start.elapsed()).as_secs();
+                outln!("\n{}[Question {}/{} - {:02}:{:02} remaining]{}", color(Role::Prompt), index + 1, num_questions, remaining / 60, remaining % 60, color(Role::Reset));
+                // Templated questions are asked with freshly picked numbers, but tallied as written
+                let instantiated: Question;
+                let asked = if question.is_templated() {
+                    instantiated = question.instantiated(&mut rng);
+                    &instantiated
+                } else {
+                    question
+                };
+                outln!("{}", math::render(asked.pick_prompt(&mut rng)));
+                let question_start = Instant::now();
+                let outcome = loop {
+                    match self.ask(asked, settings, &mut rng) {
+                        Err(SessionCommand::Back) => {
+                            errln!("{}Going back isn't available during a simulated exam{}", color(Role::Error), color(Role::Reset));
+                        },
+                        outcome => break outcome,
+                    }
+                };
+                let response = match outcome {
+                    Ok(response) => response,
+                    Err(SessionCommand::Skip) => {
+                        num_scored -= 1;
+                        continue;
+                    },
+                    Err(SessionCommand::Quit | SessionCommand::Back) => {
+                        outln!("{}Ending the exam early; unanswered questions count as incorrect.{}", color(Role::Error), color(Role::Reset));
+                        break;
+                    },
+                };
+
+                // Answers submitted after the time limit don't count
+                if start.elapsed() > time_limit {
+                    outln!("{}Time is up! The last answer was not counted.{}", color(Role::Error), color(Role::Reset));
+                    break;
+                }
+                let is_correct = response.is_correct;
+                attempt_log.record(asked, &response.given, is_correct);
+                num_correct += usize::from(is_correct);
+                question_results.push(QuestionResult {
+                    id: question.id(),
+                    correct: is_correct,
+                    seconds: question_start.elapsed().as_secs_f64(),
+                    q_type: Some(question.q_type.clone()),
+                });
+                let section = question.section.as_deref().unwrap_or("Unassigned");
+                let tally = by_section.entry(section).or_default();
+                tally.0 += usize::from(is_correct);
+                tally.1 += 1;
+            }
+
+            Self::record_session(&self.name, SessionMode::Simulate, num_correct, num_scored, question_results);
+
+            // Unanswered questions count against the score
+            let percent = if num_scored > 0 { 100.0 * num_correct as f64 / num_scored as f64 } else { 0.0 };
+            outln!("\nYou got {}/{} questions correct ({:.1}%).", num_correct, num_scored, percent);
+            if percent >= pass_percent {
+                outln!("{}PASS{} - the passing score is {:.0}%", color(Role::Success), color(Role::Reset), pass_percent);
+            } else {
+                outln!("{}FAIL{} - the passing score is {:.0}%", color(Role::Error), color(Role::Reset), pass_percent);
+            }
+            if by_section.keys().any(|section| *section != "Unassigned") {
+                outln!("Breakdown by section:");
+                for (section, (correct, attempted)) in by_section.iter() {
+                    outln!("\t{}: {}/{} ({:.0}%)", section, correct, attempted, 100.0 * *correct as f64 / *attempted as f64);
+                }
+            }
+        }
+
+        /// Runs a rapid fire round: reshuffled questions (or the authored order, for exams that
+        /// preserve it) are asked one after another, with only a brief correct/incorrect verdict,
+        /// until the given number of seconds have passed. The result is the number of correct
+        /// answers per minute, along with the accuracy.
+        pub fn rapid_fire(&self, settings: &Settings, seconds: u64) {
+            let duration = Duration::from_secs(seconds);
+            outln!("\n\n{}Rapid fire: {}{}", color(Role::Success), &self.name, color(Role::Reset));
+            outln!("Answer as many questions as you can in {} seconds; an answer given after the time is up doesn't count", seconds);
+            Self::input_optional("Press Enter to start the clock...");
+
+            let mut rng = StdRng::from_entropy();
+            let mut questions: Vec<&Question> = self.session_questions();
+            let mut num_correct = 0;
+            let mut num_answered = 0;
+            let mut question_results: Vec<QuestionResult> = Vec::new();
+            let attempt_log = AttemptLog::start(&self.name);
+            let start = Instant::now();
+            'session: loop {
+                if !self.preserve_order {
+                    questions.shuffle(&mut rng);
+                }
+                'round: for question in questions.iter() {
+                    let recalled: Question;
+                    let question = if settings.recall && question.q_type != "ue" {
+                        recalled = question.recalled();
+                        &recalled
+                    } else {
+                        question
+                    };
+                    let instantiated: Question;
+                    let question = if question.is_templated() {
+                        instantiated = question.instantiated(&mut rng);
+                        &instantiated
+                    } else {
+                        question
+                    };
+                    let remaining = duration.saturating_sub(start.elapsed()).as_secs();
+                    if settings.hide_score {
+                        outln!("\n{}[{:02}:{:02} remaining]{}", color(Role::Prompt), remaining / 60, remaining % 60, color(Role::Reset));
+                    } else {
+                        outln!("\n{}[{:02}:{:02} remaining - {} correct]{}", color(Role::Prompt), remaining / 60, remaining % 60, num_correct, color(Role::Reset));
+                    }
+                    outln!("{}", math::render(question.pick_prompt(&mut rng)));
+                    let question_start = Instant::now();
+                    let response = loop {
+                        match self.ask(question, settings, &mut rng) {
+                            Ok(response) => break response,
+                            Err(SessionCommand::Back) => {
+                                errln!("{}Going back isn't available in rapid fire mode{}", color(Role::Error), color(Role::Reset));
+                            },
+                            Err(SessionCommand::Quit) => break 'session,
+                            Err(SessionCommand::Skip) => continue 'round,
+                        }
+                    };
+                    if start.elapsed() > duration {
+                        outln!("{}Time is up! The last answer was not counted.{}", color(Role::Error), color(Role::Reset));
+                        break 'session;
+                    }
+                    let is_correct = response.is_correct;
+                    attempt_log.record(question, &response.given, is_correct);
+                    num_answered += 1;
+                    num_correct += usize::from(is_correct);
+                    question_results.push(QuestionResult {
+                        id: question.id(),
+                        correct: is_correct,
+                        seconds: question_start.elapsed().as_secs_f64(),
+                        q_type: Some(question.q_type.clone()),
+                    });
+                    // No explanations or pauses, to keep up the pace
+                    self.print_verdict(question, is_correct, settings);
+                }
+            }
+
+            if num_answered > 0 {
+                Self::record_session(&self.name, SessionMode::RapidFire, num_correct, num_answered, question_results);
+            }
+            if num_answered == 0 {
+                outln!("\nNo questions were answered.");
+                return;
+            }
+            if settings.hide_score {
+                outln!("\nYou answered {} question(s); your score is hidden, but it was saved to the study history.", num_answered);
+                return;
+            }
+            // Quitting early only counts the time that was actually spent
+            let minutes = min(start.elapsed(), duration).as_secs_f64() / 60.0;
+            outln!("\nYou got {}/{} questions correct ({:.1}%).", num_correct, num_answered, 100.0 * num_correct as f64 / num_answered as f64);
+            outln!("{}Speed: {:.1} correct answers per minute{}", color(Role::Success), num_correct as f64 / minutes, color(Role::Reset));
+        }
+
+        /// Like `ask`, except that a user entry question can be tried up to `--attempts` times; the
+        /// answer of the last try is returned, so the question only counts as correct if it was
+        /// answered within the attempts. Each wrong try is followed by the next hint when
+        /// `--attempt-hints` is set; hints revealed with `hint` carry over between tries.
+        fn ask_with_attempts(&self, question: &Question, settings: &Settings, rng: &mut StdRng) -> Result<Response, SessionCommand> {
+            let attempts = if question.q_type == "ue" { settings.ue_attempts.unwrap_or(1) } else { 1 };
+            let mut attempt = 1;
+            let mut hints_shown = 0;
+            loop {
+                let response = self.ask_with_hints(question, settings, rng, &mut hints_shown)?;
+                if response.is_correct || attempt >= attempts {
+                    return Ok(response);
+                }
+                outln!("{}Not quite; {} attempt(s) left{}", color(Role::Warning), attempts - attempt, color(Role::Reset));
+                if settings.attempt_hints {
+                    if let Some(hint) = Self::attempt_hint(question, attempt) {
+                        Self::display_hints(&[hint]);
+                    }
+                }
+                attempt += 1;
+            }
+        }
+
+        /// Helper function that gives the hint shown after the given (1-based) wrong attempt at a
+        /// user entry question: the question's own hints come first, one per attempt, followed by
+        /// the primary answer with one more leading letter of each word revealed per attempt
+        /// (e.g., "P____" and then "Pa___"). Regular expressions aren't masked, since they aren't
+        /// meant to be typed as written.
+        fn attempt_hint(question: &Question, attempt: usize) -> Option<String> {
+            // Empty hints are left out, like they are when `hint` is entered
+            let hints: Vec<&String> = question.choices.iter().filter(|hint| !hint.is_empty()).collect();
+            if let Some(hint) = hints.get(attempt - 1) {
+                return Some(hint.to_string());
+            }
+            if question.match_mode() == MatchMode::Regex {
+                return None;
+            }
+            let revealed = attempt - hints.len();
+            let answer = question.answer.first()?;
+            let masked: Vec<String> = answer
+                .split_whitespace()
+                .map(|word| word.chars().enumerate().map(|(index, c)| if index < revealed || !c.is_alphanumeric() { c } else { '_' }).collect())
+                .collect();
+            Some(masked.join(" "))
+        }
+
+        /// Displays the choices for a question (if any), collects the user's answer, and grades it;
+        /// the prompt should already be displayed. If the user enters an in-session command instead
+        /// of an answer, the command is returned as the error.
+        fn ask(&self, question: &Question, settings: &Settings, rng: &mut StdRng) -> Result<Response, SessionCommand> {
+            self.ask_with_hints(question, settings, rng, &mut 0)
+        }
+
+        /// Like `ask`, except that hints already revealed (counted in `hints_shown`) aren't shown
+        /// again; the response counts every hint revealed, including earlier ones.
+        fn ask_with_hints(&self, question: &Question, settings: &Settings, rng: &mut StdRng, hints_shown: &mut usize) -> Result<Response, SessionCommand> {
+            let given = self.collect_answer(question, settings, rng, hints_shown)?;
+            let is_correct = self.grade(question, &given);
+            Ok(Response { given, is_correct, hints: *hints_shown })
+        }
+
+        /// Helper function that finds the follow-up question triggered by the given answer, using
+        /// the question's `followups` (answer to follow-up question ID). Answers are compared
+        /// case-insensitively, and the alphabetically first matching answer wins.
+        fn followup(&self, question: &Question, given: &[String]) -> Option<&Question> {
+            let mut triggers: Vec<(&String, &String)> = question.followups.iter().collect();
+            triggers.sort();
+            triggers
+                .into_iter()
+                .find(|(answer, _)| given.iter().any(|given| given.trim().eq_ignore_ascii_case(answer.trim())))
+                .and_then(|(_, id)| self.questions.iter().find(|q| q.id() == *id))
+        }
+
+        /// Helper function that determines whether the given answer to a question is correct.
+        /// Multiple choice and user entry questions take a single answer, while the answers to a
+        /// multiple select question must match every correct answer (in any order, unless the
+        /// question is `ordered`).
+        fn grade(&self, question: &Question, given: &[String]) -> bool {
+            match (question.q_type.as_str(), given) {
+                ("mc", [answer]) => question.answer.first() == Some(answer),
+                ("ms", _) if question.ordered => given == question.answer.as_slice(),
+                ("ms", _) => {
+                    let selected: HashSet<&String> = given.iter().collect();
+                    selected.len() == question.answer.len() && question.answer.iter().all(|answer| selected.contains(answer))
+                },
+                ("ue", [answer]) => match question.match_mode() {
+                    MatchMode::Exact => {
+                        let answer = self.normalize(answer);
+                        self.accepted_answers(question).iter().any(|accepted| self.normalize(accepted) == answer)
+                    },
+                    MatchMode::Contains => {
+                        let answer = self.normalize(answer).to_lowercase();
+                        self.accepted_answers(question).iter().any(|accepted| answer.contains(&self.normalize(accepted).to_lowercase()))
+                    },
+                    MatchMode::Regex => {
+                        let answer = self.normalize(answer);
+                        let mut patterns = self.patterns.borrow_mut();
+                        // Patterns that don't compile are reported by `validate`, and never match
+                        question.answer.iter().any(|pattern| {
+                            let regex = patterns.entry(pattern.clone()).or_insert_with_key(|pattern| Self::compile_pattern(pattern).ok());
+                            regex.as_ref().is_some_and(|regex| regex.is_match(&answer))
+                        })
+                    },
+                    MatchMode::Numeric => match parse_number(answer) {
+                        Some(answer) => question.answer
+                            .iter()
+                            .filter_map(|accepted| parse_number(accepted))
+                            .any(|accepted| (answer - accepted).abs() <= NUMERIC_TOLERANCE),
+                        None => false,
+                    },
+                    MatchMode::Keywords => {
+                        let covered = Self::key_terms(answer);
+                        question.answer.iter().any(|accepted| {
+                            let key_terms = Self::key_terms(accepted);
+                            // Answers too short to have key terms are compared in full instead
+                            match key_terms.len() {
+                                0 => accepted.trim().eq_ignore_ascii_case(answer.trim()),
+                                num_terms => 2 * key_terms.intersection(&covered).count() >= num_terms,
+                            }
+                        })
+                    },
+                },
+                _ => false,
+            }
+        }
+
+        /// Helper function that compiles the accepted answer of a `regex` question, anchored so
+        /// that the pattern has to match the whole answer rather than any part of it.
+        fn compile_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+            Regex::new(&format!("^(?:{})$", pattern))
+        }
+
+        /// Helper function that displays the choices for a question (if any) and collects the
+        /// user's answer: the selected choice(s) for multiple choice and multiple select questions,
+        /// or the entered text for user entry questions. Each `hint` entered at a user entry question
+        /// reveals its next hint, counted in `hints_shown`. A question whose `q_type` isn't one of
+        /// `mc`, `ms`, or `ue` is skipped.
+        fn collect_answer(&self, question: &Question, settings: &Settings, rng: &mut StdRng, hints_shown: &mut usize) -> Result<Vec<String>, SessionCommand> {
+            // logic depends on question type
+            match question.q_type.as_ref() {
+                "mc" => {
+                    let choices = Self::display_choices_and_collect(question, settings, rng);
+                    if Self::uses_arrow_keys(question, settings) {
+                        if let Some(choice) = Self::select_with_arrow_keys(&choices)? {
+                            return Ok(vec![choice]);
+                        }
+                    }
+
+                    // Get the user's answer based on the letter prefix printed above or the choice text
+                    let user_answer: String = loop {
+                        let user_choice: String = self.answer_input("Enter answer (e.g., 'a', 'b', 'c', ...): ", question)?;
+                        match Self::resolve_choice(&user_choice, &choices) {
+                            Some(choice) => break choice.to_string(),
+                            // Rather than silently picking one of several letters (e.g., "ab")
+                            None if Self::is_several_letters(&user_choice, choices.len()) => {
+                                errln!("{}Please enter a single letter; multiple choice questions have only one answer!{}", color(Role::Error), color(Role::Reset));
+                            },
+                            None => errln!("{}Please pick a valid answer!{}", color(Role::Error), color(Role::Reset)),
+                        }
+                    };
+
+                    Ok(vec![user_answer])
+                },
+                "ms" => {
+                    let choices = Self::display_choices_and_collect(question, settings, rng);
+                    // Only reveal how many choices to select when asked to, since some exams
+                    // intentionally hide it
+                    let prompt = match (settings.show_select_count, question.ordered) {
+                        (true, true) => format!("Enter comma-separated answer in order (select {}): ", question.answer.len()),
+                        (true, false) => format!("Enter comma-separated answer (select {}): ", question.answer.len()),
+                        (false, true) => "Enter comma-separated answer in order (e.g., 'b, a, c'): ".to_string(),
+                        (false, false) => "Enter comma-separated answer (e.g., 'a, b', or 'c'): ".to_string(),
+                    };
+                    // Get the user's multiple select answer(s); a confusing question can be skipped,
+                    // and is skipped automatically after too many invalid selections
+                    let mut num_invalid = 0;
+                    let user_sel = loop {
+                        let input = self.answer_input(&prompt, question)?;
+                        match Self::parse_selection(&input, &choices, &mut num_invalid) {
+                            SelectEntry::Chosen(selected) => break selected,
+                            SelectEntry::Skip => return Err(SessionCommand::Skip),
+                            SelectEntry::GiveUp => {
+                                errln!("{}Skipping the question after {} invalid selections{}", color(Role::Warning), num_invalid, color(Role::Reset));
+                                return Err(SessionCommand::Skip);
+                            },
+                            SelectEntry::Invalid(invalid) => {
+                                errln!("{}Not among the available choices: {}; enter the letters of the choices, or 'skip' to move on{}",
+                                    color(Role::Error), invalid.join(", "), color(Role::Reset));
+                            },
+                        }
+                    };
+                    let mut selected: Vec<String> = user_sel.into_iter().cloned().collect();
+                    // Sorted so that the selection is recorded the same way regardless of order,
+                    // unless the order is part of the answer
+                    if !question.ordered {
+                        selected.sort();
+                        selected.dedup();
+                    }
+                    Ok(selected)
+                },
+                "ue" => {
+                    // Collect the hint(s), if any
+                    let hints = Self::display_choices_and_collect(question, settings, rng);
+                    // Answers must be entered twice when confirmation is requested
+                    let confirm = settings.confirm_ue || question.confirm;
+                    // Multi-line answers continue until a line with only a period; commands are only
+                    // recognized on the first line
+                    if question.multiline {
+                        outln!("{}Your answer can span multiple lines; end it with a line containing only '.'{}", color(Role::Info), color(Role::Reset));
+                    }
+                    // Get the user's input; display prompt and reveal the next hint, if available
+                    let user_ans: String = loop {
+                        match hints.len() {
+                            num if num > 0 => {
+                                let prompt = match num.saturating_sub(*hints_shown) {
+                                    0 => "Enter your answer: ".to_string(),
+                                    remaining => format!("Enter your answer (or enter 'hint' for a hint; {} left): ", remaining),
+                                };
+                                let input = self.answer_input(&prompt, question)?;
+                                if input.eq_ignore_ascii_case("hint") {
+                                    match hints.get(*hints_shown) {
+                                        Some(hint) => {
+                                            Self::display_hints(std::slice::from_ref(hint));
+                                            *hints_shown += 1;
+                                        },
+                                        None => errln!("{}No more hints; all {} have been shown{}", color(Role::Error), num, color(Role::Reset)),
+                                    }
+                                    continue;
+                                }
+                                let input = if question.multiline { Self::input_paragraph(input) } else { input };
+                                if !confirm || Self::confirm_entry(&input) {
+                                    break input
+                                }
+                            },
+                            _ => {
+                                let input = self.answer_input("Enter your answer: ", question)?;
+                                if input.eq_ignore_ascii_case("hint") {
+                                    errln!("{}This question doesn't have any hints...{}", color(Role::Error), color(Role::Reset));
+                                    continue;
+                                }
+                                let input = if question.multiline { Self::input_paragraph(input) } else { input };
+                                if !confirm || Self::confirm_entry(&input) {
+                                    break input
+                                }
+                            }
+                        }
+                    };
+                    Ok(vec![user_ans])
+                },
+                // `check` reports these; skipping keeps the rest of the session going
+                q_type => {
+                    errln!("{}Skipping a question with the unrecognized q_type '{}'{}", color(Role::Error), q_type, color(Role::Reset));
+                    Err(SessionCommand::Skip)
+                },
+            }
+        }
+
+        /// Helper function that applies the exam's `normalize` rules to an answer; punctuation is
+        /// removed first so that, e.g., "The, end" loses its article, and whitespace is collapsed
+        /// last to tidy up after the other rules.
+        fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+            if self.normalize.is_empty() {
+                return Cow::Borrowed(text);
+            }
+            let mut text = text.to_string();
+            if self.normalize.contains(&Normalization::Punctuation) {
+                text.retain(|c| c.is_alphanumeric() || c.is_whitespace());
+            }
+            if self.normalize.contains(&Normalization::Lowercase) {
+                text = text.to_lowercase();
+            }
+            if self.normalize.contains(&Normalization::Articles) {
+                let trimmed = text.trim_start();
+                let article = trimmed
+                    .split_once(char::is_whitespace)
+                    .filter(|(word, _)| ["the", "a", "an"].iter().any(|article| word.eq_ignore_ascii_case(article)));
+                if let Some((_, rest)) = article {
+                    text = rest.trim_start().to_string();
+                }
+            }
+            if self.normalize.contains(&Normalization::Whitespace) {
+                text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+            }
+            Cow::Owned(text)
+        }
+
+        /// Helper function that collects every accepted answer to a user entry question: the
+        /// question's own answers, plus every synonym of those answers from the exam's `synonyms`.
+        fn accepted_answers<'a>(&'a self, question: &'a Question) -> HashSet<&'a str> {
+            let mut accepted: HashSet<&str> = question.answer.iter().map(String::as_str).collect();
+            for (term, synonyms) in self.synonyms.iter() {
+                // A term and its synonyms are interchangeable, so any of them can match an answer
+                let group = || std::iter::once(term).chain(synonyms.iter());
+                if group().any(|word| question.answer.contains(word)) {
+                    accepted.extend(group().map(String::as_str));
+                }
+            }
+            accepted
+        }
+
+        /// Helper function that interprets an entry at a multiple select prompt: a comma-separated
+        /// list of choices, or `skip`. Each invalid selection is counted in `num_invalid`, and the
+        /// question is given up on once `MAX_INVALID_SELECTIONS` have been entered.
+        fn parse_selection<'a>(input: &str, choices: &'a [String], num_invalid: &mut usize) -> SelectEntry<'a> {
+            if input.trim().eq_ignore_ascii_case("skip") {
+                return SelectEntry::Skip;
+            }
+            let entries: Vec<(&str, Option<&String>)> = input
+                .split(',')
+                .map(|choice| (choice.trim(), Self::resolve_choice(choice, choices)))
+                .collect();
+            let invalid: Vec<String> = entries
+                .iter()
+                .filter(|(_, selected)| selected.is_none())
+                .map(|(choice, _)| format!("'{}'", choice))
+                .collect();
+            if invalid.is_empty() {
+                return SelectEntry::Chosen(entries.into_iter().filter_map(|(_, selected)| selected).collect());
+            }
+            *num_invalid += 1;
+            if *num_invalid >= MAX_INVALID_SELECTIONS {
+                SelectEntry::GiveUp
+            } else {
+                SelectEntry::Invalid(invalid)
+            }
+        }
+
+        /// Helper function that matches the user's input to one of the displayed choices. A single
+        /// letter is matched to the choice with that letter prefix first; otherwise the input is
+        /// compared (case-insensitively) against the text of each choice, with or without any
+        /// enumeration the choice carries.
+        fn resolve_choice<'a>(input: &str, choices: &'a [String]) -> Option<&'a String> {
+            let input = input.trim();
+            let mut chars = input.chars();
+            let letter_match = match (chars.next(), chars.next()) {
+                (Some(letter), None) if letter.is_ascii_alphabetic() => {
+                    choices.get((letter.to_ascii_lowercase() as u8 - b'a') as usize)
+                },
+                _ => None,
+            };
+            letter_match.or_else(|| Self::match_choice_text(input, choices))
+        }
+
+        /// Helper function that determines whether the input is several choice letters (e.g., "ab"
+        /// or "a, c") rather than a single choice.
+        fn is_several_letters(input: &str, num_choices: usize) -> bool {
+            let letters: Vec<char> = input.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+            letters.len() > 1 && letters.iter().all(|letter| {
+                letter.is_ascii_alphabetic() && ((letter.to_ascii_lowercase() as u8 - b'a') as usize) < num_choices
+            })
+        }
+
+        /// Helper function that matches text to the choice it names (case-insensitively), with or
+        /// without any enumeration the choice carries.
+        fn match_choice_text<'a>(input: &str, choices: &'a [String]) -> Option<&'a String> {
+            let input = input.trim();
+            choices.iter()
+                .find(|choice| choice.trim().eq_ignore_ascii_case(input))
+                .or_else(|| choices.iter().find(|choice| strip_enumeration(choice).is_some_and(|text| text.eq_ignore_ascii_case(input))))
+        }
+
+        /// Helper function that renders a progress bar (e.g., `[#####-----] 5/10`) sized to the
+        /// width of the terminal. If the terminal width can't be detected, then only the plain
+        /// fraction is displayed.
+        fn display_progress(completed: usize, total: usize) {
+            let fraction = format!("{}/{}", completed, total);
+            // Leave room for the brackets and the space preceding the fraction
+            let bar_width = terminal_size()
+                .map(|(Width(width), _)| width as usize)
+                .and_then(|width| width.checked_sub(fraction.len() + 3))
+                .filter(|width| *width > 0);
+            match bar_width {
+                Some(width) if total > 0 => {
+                    let filled = width * completed / total;
+                    outln!("[{}{}{}{}] {}", color(Role::Success), "#".repeat(filled), color(Role::Reset), "-".repeat(width - filled), fraction);
+                },
+                _ => outln!("Progress: {}", fraction),
+            }
+        }
+
+        /// Helper function that displays the letter grade for a percentage score, colored by band.
+        ///
+        /// # Argument
+        ///
+        /// * `cutoffs` - the minimum percentages for an A, B, C, and D; anything lower is an F.
+        fn display_grade(percent: f64, cutoffs: [f64; 4]) {
+            let grade = ['A', 'B', 'C', 'D']
+                .into_iter()
+                .zip(cutoffs)
+                .find(|(_, cutoff)| percent >= *cutoff)
+                .map_or('F', |(grade, _)| grade);
+            let role = match grade {
+                'A' | 'B' => Role::Success,
+                'C' => Role::Warning,
+                _ => Role::Error,
+            };
+            outln!("Grade: {}{}{}", color(role), grade, color(Role::Reset));
+        }
+
+        /// Helper function that computes the score under negative marking: one point for each
+        /// correct answer, minus the `deducted` points (for incorrect answers and hints). The score
+        /// is floored at zero unless `allow_negative` is set.
+        fn penalized_score(num_correct: usize, deducted: f64, allow_negative: bool) -> f64 {
+            let score = num_correct as f64 - deducted;
+            if allow_negative { score } else { score.max(0.0) }
+        }
+
+        /// Helper function that computes the 95% Wilson score interval around the fraction of
+        /// questions answered correctly. Unlike the raw percentage, the interval widens when only a
+        /// few questions were answered, giving an honest estimate of the user's true score.
+        ///
+        /// # Arguments
+        ///
+        /// * `correct` - the number of questions answered correctly
+        /// * `answered` - the number of questions answered
+        ///
+        /// # Returns
+        ///
+        /// The (lower, upper) bounds as fractions between 0 and 1, or `None` if nothing was answered
+        fn wilson_interval(correct: usize, answered: usize) -> Option<(f64, f64)> {
+            if answered == 0 {
+                return None;
+            }
+            // z-score for a 95% confidence level
+            const Z: f64 = 1.96;
+            let n = answered as f64;
+            let p = correct as f64 / n;
+            let denominator = 1.0 + Z * Z / n;
+            let center = (p + Z * Z / (2.0 * n)) / denominator;
+            let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+            Some(((center - margin).max(0.0), (center + margin).min(1.0)))
+        }
+
+        /// Helper function that records a finished session to the study history; failing to do so
+        /// only warrants a warning since the session itself is over.
+        fn record_session(name: &str, mode: SessionMode, num_correct: usize, num_answered: usize, questions: Vec<QuestionResult>) {
+            if let Err(e) = history::record(name, mode, num_correct, num_answered, questions) {
+                errln!("{}Unable to record study session:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+            }
+        }
+
+        /// Helper function that rings the terminal bell the given number of times so that results
+        /// can be heard without looking at the screen; nothing is written when stdout isn't a
+        /// terminal.
+        fn ring_bell(times: usize) {
+            if !stdout_is_terminal() {
+                return;
+            }
+            for ring in 0..times {
+                if ring > 0 {
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                }
+                print!("\x07");
+                stdout().flush().expect("Unable to flush stdout...");
+            }
+        }
+
+        /// Helper function that reveals the correct answer(s) after the user answers incorrectly.
+        /// Multiple select questions always reveal every answer since all of them are required;
+        /// otherwise the `reveal` setting decides whether only the primary (first) answer is shown.
+        /// The answers are joined with commas and substituted for `{answers}` in the reveal
+        /// template.
+        fn reveal_answer(question: &Question, settings: &Settings) {
+            let answers: Vec<&str> = match (&settings.reveal, question.answer.first()) {
+                (Reveal::Primary, Some(primary)) if question.q_type != "ms" => vec![primary.trim()],
+                _ => question.answer.iter().map(|answer| answer.trim()).collect(),
+            };
+            let template = settings.reveal_template.as_deref().unwrap_or(DEFAULT_REVEAL_TEMPLATE);
+            let revealed = template.replace("{answers}", &math::render(&answers.join(", ")));
+            outln!("{}{}{}", color(Role::Explanation), revealed, color(Role::Reset));
+        }
+
+        /// Helper function for displaying hints for user entry questions.
+        fn display_hints(hints_ref: &[String]) {
+            hints_ref.iter().for_each(|hint| {
+                outln!("{}\t{}Hint: {}{}{}", color(Role::Hint), style(START_ITALICS), math::render(hint), style(END_ITALICS), color(Role::Reset));
+            })
+        }
+
+        /// Helper function that iterates over the `choices` field of the parameter `Question`.
+        /// The way a choice/option will be displayed depends on the `q_type` field; choices are
+        /// shuffled so that they aren't always presented in the same order (or kept sorted
+        /// alphabetically with `--sorted`), except for pinned choices (e.g., "None of the above")
+        /// which always come last. Choices selected with the arrow keys are drawn when selecting.
+        fn display_choices_and_collect(question_ref: &Question, settings: &Settings, rng: &mut StdRng) -> Vec<String> {
+            // Sorted first, so that the shuffled order only depends on the generator's seed
+            let mut choices: Vec<&String> = question_ref.choices.iter().collect();
+            choices.sort();
+            if !settings.sorted {
+                choices.shuffle(rng);
+            }
+            // The sort is stable, so unpinned choices (`None`) keep their shuffled order
+            choices.sort_by_key(|choice| question_ref.pinned_position(choice));
+            match question_ref.q_type.as_str() {
+                "mc" | "ms" => {
+                    let choices: Vec<String> = choices.into_iter().map(String::to_string).collect();
+                    if !Self::uses_arrow_keys(question_ref, settings) {
+                        Self::display_choice_labels(&Self::choice_labels(&choices), settings.columns);
+                    }
+                    choices
+                },
+                // Don't print hint(s) - let the user decide; empty hints are left out, and the rest are
+                // revealed one at a time in the order they were authored (like `--attempt-hints`),
+                // from subtle to revealing
+                "ue" => question_ref.choices.iter().filter(|choice| !choice.is_empty()).cloned().collect(),
+                _ => Vec::new(),
+            }
+        }
+
+        /// Helper function that prefixes each choice with its letter, as displayed. Choices that carry
+        /// their own enumeration (e.g., "a) ...") would otherwise be displayed with two letters; to
+        /// avoid mangling content, it's only removed when every choice has one.
+        fn choice_labels(choices: &[String]) -> Vec<String> {
+            let strip_enumerations = choices.iter().all(|choice| strip_enumeration(choice).is_some());
+            choices
+                .iter()
+                .enumerate()
+                .map(|(index, choice)| {
+                    let text = if strip_enumerations { strip_enumeration(choice).unwrap_or(choice) } else { choice };
+                    format!("{}.) {}", (index as u8 + b'a') as char, math::render(text))
+                })
+                .collect()
+        }
+
+        /// Helper function that determines whether a question's answer is selected with the arrow
+        /// keys, which requires `--arrow-keys`, a multiple choice question, and an interactive
+        /// terminal.
+        fn uses_arrow_keys(question: &Question, settings: &Settings) -> bool {
+            settings.arrow_keys && question.q_type == "mc" && stdout_is_terminal() && stdin().is_terminal()
+        }
+
+        /// Helper function that lets the user select one of the choices with the arrow keys.
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Option<String>, SessionCommand>` - the selected choice, `None` if the user
+        ///   pressed Escape to type the answer (or a command) instead, or `Err(SessionCommand::Quit)`
+        ///   if the user pressed Ctrl+C.
+        fn select_with_arrow_keys(choices: &[String]) -> Result<Option<String>, SessionCommand> {
+            let labels = Self::choice_labels(choices);
+            outln!("{}Use the up/down arrow keys and Enter to answer, or Esc to type an answer or command{}", color(Role::Info), color(Role::Reset));
+            let selection = menu::select(&labels, color(Role::Success), color(Role::Reset));
+            // The menu draws the choices itself, so they're only copied to the transcript here
+            if selection.is_ok() {
+                labels.iter().for_each(|label| transcribe(&format!("\t{}", label)));
+            }
+            match selection {
+                Ok(Selection::Chosen(index)) => {
+                    outln!("Selected: {}", labels[index]);
+                    Ok(Some(choices[index].clone()))
+                },
+                Ok(Selection::Typed) => Ok(None),
+                Ok(Selection::Interrupted) => Err(SessionCommand::Quit),
+                Err(e) => {
+                    errln!("{}Unable to select with the arrow keys; type the answer instead:\t{}{}", color(Role::Warning), e, color(Role::Reset));
+                    Self::display_choice_labels(&labels, false);
+                    Ok(None)
+                },
+            }
+        }
+
+        /// Helper function that prints the lettered choices of a question, one per line, or in
+        /// columns (filled top to bottom) when requested and the choices are short and numerous
+        /// enough to fit several side by side in the terminal.
+        fn display_choice_labels(labels: &[String], columns: bool) {
+            let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or_default() + CHOICE_COLUMN_GAP;
+            // Leave room for the tab that indents the choices
+            let num_columns = if columns && labels.len() >= MIN_COLUMN_CHOICES {
+                (terminal_width().saturating_sub(8) / label_width).clamp(1, MAX_CHOICE_COLUMNS)
+            } else {
+                1
+            };
+            let num_rows = labels.len().div_ceil(num_columns);
+            for row in 0..num_rows {
+                let line: String = (0..num_columns)
+                    .filter_map(|column| labels.get(column * num_rows + row))
+                    .map(|label| format!("{:<width$}", label, width = label_width))
+                    .collect();
+                outln!("{}\t{}{}", color(Role::Hint), line.trim_end(), color(Role::Reset));
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EXAM: &str = r#"{
+            "name": "Networking",
+            "questions": [
+                {"q_type": "mc", "prompt": "Which layer routes packets?", "choices": ["Physical", "Data link", "Network", "Transport", "Session"],
+                 "answer": ["Network"], "explanation": "@file:notes/routing.md"},
+                {"q_type": "ms", "prompt": "Web ports?", "choices": ["22", "80", "443", "8080"], "answer": ["80", "443"],
+                 "followups": {"22": "ssh", "80": "http", "443": "https"}}
+            ]
+        }"#;
+
+        #[test]
+        fn content_hash_is_the_same_every_time_the_exam_is_loaded() {
+            let dir = env::temp_dir().join(format!("term_prep_plus_hash_{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("exam.json");
+            fs::write(&path, EXAM).unwrap();
+
+            let first = Exam::load_exam(&path, &mut Vec::new()).unwrap();
+            // Spelled differently, which changes the directory explanation files are read from
+            let second = Exam::load_exam(&dir.join(".").join("exam.json"), &mut Vec::new()).unwrap();
+            let parsed: Exam = serde_json::from_str(EXAM).unwrap();
+            fs::remove_dir_all(&dir).unwrap();
+
+            assert_eq!(first.content_hash(), second.content_hash());
+            assert_eq!(first.content_hash(), parsed.content_hash());
+        }
+
+        #[test]
+        fn combining_exams_removes_a_question_found_in_both_files() {
+            let dir = env::temp_dir().join(format!("term_prep_plus_combine_{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let paths = vec![dir.join("first.json"), dir.join("second.json")];
+            for path in paths.iter() {
+                fs::write(path, EXAM).unwrap();
+            }
+
+            let combined = Exam::combine_exams(&paths).unwrap();
+            fs::remove_dir_all(&dir).unwrap();
+
+            assert_eq!(combined.questions.len(), 2);
+        }
+
+        #[test]
+        fn regex_answers_must_match_in_full_after_normalizing() {
+            let exam: Exam = serde_json::from_str(r#"{
+                "name": "Pets",
+                "normalize": ["lowercase"],
+                "questions": [{"q_type": "ue", "prompt": "Name a feline", "answer": ["cats?"], "match_mode": "regex"}]
+            }"#).unwrap();
+            let question = exam.questions.first().unwrap();
+            let grade = |answer: &str| exam.grade(question, &[answer.to_string()]);
+
+            assert!(grade("cat"));
+            assert!(grade("Cats"));
+            assert!(!grade("concatenate"));
+            assert!(!grade("a cat"));
+        }
+
+        #[test]
+        fn hints_are_revealed_in_the_authored_order() {
+            let question: Question = serde_json::from_str(r#"{
+                "q_type": "ue", "prompt": "Which letter comes first?", "answer": ["A"],
+                "choices": ["Think of the alphabet", "", "It's a vowel"]
+            }"#).unwrap();
+            let settings = Settings::default();
+            let mut rng = StdRng::seed_from_u64(0);
+
+            assert_eq!(Exam::display_choices_and_collect(&question, &settings, &mut rng), vec!["Think of the alphabet", "It's a vowel"]);
+            assert_eq!(Exam::attempt_hint(&question, 1).as_deref(), Some("Think of the alphabet"));
+            assert_eq!(Exam::attempt_hint(&question, 2).as_deref(), Some("It's a vowel"));
+            assert_eq!(Exam::attempt_hint(&question, 3).as_deref(), Some("A"));
+        }
+
+        #[test]
+        fn multiple_select_retries_invalid_selections_until_skipped() {
+            let choices: Vec<String> = ["22", "80", "443"].iter().map(|choice| choice.to_string()).collect();
+            let mut num_invalid = 0;
+            for _ in 1..MAX_INVALID_SELECTIONS {
+                assert_eq!(Exam::parse_selection("a, z", &choices, &mut num_invalid), SelectEntry::Invalid(vec!["'z'".to_string()]));
+            }
+            assert_eq!(num_invalid, MAX_INVALID_SELECTIONS - 1);
+            assert_eq!(Exam::parse_selection(" Skip ", &choices, &mut num_invalid), SelectEntry::Skip);
+            assert_eq!(Exam::parse_selection("b, 443", &choices, &mut num_invalid), SelectEntry::Chosen(vec![&choices[1], &choices[2]]));
+            assert_eq!(Exam::parse_selection("zz", &choices, &mut num_invalid), SelectEntry::GiveUp);
+        }
+    }
+}
+
+mod math {
+    /// Converts simple LaTeX math between `$` delimiters into Unicode for display in a terminal
+    /// (e.g., `$2^{n}$` becomes `2ⁿ` and `$\alpha$` becomes `α`). A span is only converted when
+    /// every part of it has a Unicode equivalent; anything else, including lone `$` signs, is left
+    /// untouched.
+    pub fn render(text: &str) -> String {
+        let mut rendered = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('$') {
+            rendered.push_str(&rest[..start]);
+            let after_start = &rest[start + 1..];
+            let Some(end) = after_start.find('$') else {
+                rest = &rest[start..];
+                break;
+            };
+            let span = &after_start[..end];
+            match convert(span) {
+                Some(converted) => {
+                    rendered.push_str(&converted);
+                    rest = &after_start[end + 1..];
+                },
+                None => {
+                    // Keep the opening `$` and the span, but let the closing `$` open a new span
+                    rendered.push('$');
+                    rendered.push_str(span);
+                    rest = &after_start[end..];
+                },
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Helper function that converts the contents of a single math span, or returns `None` if the
+    /// span isn't LaTeX or contains something that can't be converted.
+    fn convert(span: &str) -> Option<String> {
+        if !span.contains(['^', '_', '\\']) {
+            return None;
+        }
+        let mut converted = String::new();
+        let mut chars = span.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let mut command = String::new();
+                    while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+                        command.push(letter);
+                    }
+                    converted.push(symbol(&command)?);
+                },
+                '^' | '_' => {
+                    let group: String = match chars.next()? {
+                        '{' => {
+                            let mut group = String::new();
+                            loop {
+                                match chars.next()? {
+                                    '}' => break group,
+                                    inner => group.push(inner),
+                                }
+                            }
+                        },
+                        single => single.to_string(),
+                    };
+                    let script = if c == '^' { superscript } else { subscript };
+                    for inner in group.chars() {
+                        converted.push(script(inner)?);
+                    }
+                },
+                '{' | '}' => return None,
+                other => converted.push(other),
+            }
+        }
+        Some(converted)
+    }
+
+    /// Helper function that maps a LaTeX command (without the backslash) to its Unicode symbol.
+    fn symbol(command: &str) -> Option<char> {
+        Some(match command {
+            "alpha" => 'α', "beta" => 'β', "gamma" => 'γ', "delta" => 'δ', "epsilon" => 'ε',
+            "theta" => 'θ', "lambda" => 'λ', "mu" => 'μ', "pi" => 'π', "rho" => 'ρ',
+            "sigma" => 'σ', "tau" => 'τ', "phi" => 'φ', "omega" => 'ω',
+            "Gamma" => 'Γ', "Delta" => 'Δ', "Theta" => 'Θ', "Lambda" => 'Λ', "Pi" => 'Π',
+            "Sigma" => 'Σ', "Phi" => 'Φ', "Omega" => 'Ω',
+            "times" => '×', "cdot" => '·', "div" => '÷', "pm" => '±', "leq" | "le" => '≤',
+            "geq" | "ge" => '≥', "neq" | "ne" => '≠', "approx" => '≈', "infty" => '∞',
+            "sqrt" => '√', "sum" => '∑', "in" => '∈', "to" | "rightarrow" => '→',
+            "leftarrow" => '←', "oplus" => '⊕', "land" | "wedge" => '∧', "lor" | "vee" => '∨',
+            "neg" | "lnot" => '¬',
+            _ => return None,
+        })
+    }
+
+    /// Helper function that maps a character to its Unicode superscript form, if there is one.
+    fn superscript(c: char) -> Option<char> {
+        Some(match c {
+            '0' => '⁰', '1' => '¹', '2' => '²', '3' => '³', '4' => '⁴',
+            '5' => '⁵', '6' => '⁶', '7' => '⁷', '8' => '⁸', '9' => '⁹',
+            '+' => '⁺', '-' => '⁻', '=' => '⁼', '(' => '⁽', ')' => '⁾',
+            'a' => 'ᵃ', 'b' => 'ᵇ', 'c' => 'ᶜ', 'd' => 'ᵈ', 'e' => 'ᵉ', 'f' => 'ᶠ', 'g' => 'ᵍ',
+            'h' => 'ʰ', 'i' => 'ⁱ', 'j' => 'ʲ', 'k' => 'ᵏ', 'l' => 'ˡ', 'm' => 'ᵐ', 'n' => 'ⁿ',
+            'o' => 'ᵒ', 'p' => 'ᵖ', 'r' => 'ʳ', 's' => 'ˢ', 't' => 'ᵗ', 'u' => 'ᵘ', 'v' => 'ᵛ',
+            'w' => 'ʷ', 'x' => 'ˣ', 'y' => 'ʸ', 'z' => 'ᶻ',
+            _ => return None,
+        })
+    }
+
+    /// Helper function that maps a character to its Unicode subscript form, if there is one.
+    fn subscript(c: char) -> Option<char> {
+        Some(match c {
+            '0' => '₀', '1' => '₁', '2' => '₂', '3' => '₃', '4' => '₄',
+            '5' => '₅', '6' => '₆', '7' => '₇', '8' => '₈', '9' => '₉',
+            '+' => '₊', '-' => '₋', '=' => '₌', '(' => '₍', ')' => '₎',
+            'a' => 'ₐ', 'e' => 'ₑ', 'h' => 'ₕ', 'i' => 'ᵢ', 'j' => 'ⱼ', 'k' => 'ₖ', 'l' => 'ₗ',
+            'm' => 'ₘ', 'n' => 'ₙ', 'o' => 'ₒ', 'p' => 'ₚ', 'r' => 'ᵣ', 's' => 'ₛ', 't' => 'ₜ',
+            'u' => 'ᵤ', 'v' => 'ᵥ', 'x' => 'ₓ',
+            _ => return None,
+        })
+    }
+}
+
+mod formula {
+    use std::collections::BTreeMap;
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    /// Evaluates an arithmetic expression over the given parameters, e.g., `2^(32 - mask) - 2`.
+    /// Supports numbers, parameter names, parentheses, `+`, `-`, `*`, `/`, `%`, `^` (which binds
+    /// tightest, and groups from the right), and the functions `abs`, `sqrt`, `floor`, `ceil`,
+    /// `round`, `log2`, `log10`, `min`, and `max`.
+    ///
+    /// # Errors
+    ///
+    /// * describes the problem when the expression is malformed, uses an unknown parameter or
+    ///   function, or its result isn't a finite number (e.g., division by zero).
+    pub fn evaluate(expression: &str, params: &BTreeMap<String, f64>) -> Result<f64, String> {
+        let mut parser = Parser { chars: expression.chars().peekable(), params };
+        let value = parser.sum()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.chars.peek() {
+            return Err(format!("unexpected '{}' in '{}'", c, expression));
+        }
+        if !value.is_finite() {
+            return Err(format!("'{}' doesn't evaluate to a finite number", expression));
+        }
+        Ok(value)
+    }
+
+    /// Formats a number for display: whole numbers without a decimal point, and anything else
+    /// rounded to at most 2 decimal places (e.g., `254` and `3.14`).
+    pub fn format(value: f64) -> String {
+        if value.fract() == 0.0 && value.abs() < 1e15 {
+            format!("{}", value as i64)
+        } else {
+            let rounded = format!("{:.2}", value);
+            rounded.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+    }
+
+    /// Recursive descent parser that evaluates while parsing; each method parses one level of
+    /// precedence.
+    struct Parser<'a> {
+        chars: Peekable<Chars<'a>>,
+        params: &'a BTreeMap<String, f64>,
+    }
+
+    impl Parser<'_> {
+        fn skip_whitespace(&mut self) {
+            while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        }
+
+        /// Consumes the given operator if it's next (after any whitespace).
+        fn eat(&mut self, operator: char) -> bool {
+            self.skip_whitespace();
+            self.chars.next_if_eq(&operator).is_some()
+        }
+
+        /// Terms separated by `+` and `-`.
+        fn sum(&mut self) -> Result<f64, String> {
+            let mut value = self.product()?;
+            loop {
+                if self.eat('+') {
+                    value += self.product()?;
+                } else if self.eat('-') {
+                    value -= self.product()?;
+                } else {
+                    return Ok(value);
+                }
+            }
+        }
+
+        /// Factors separated by `*`, `/`, and `%`.
+        fn product(&mut self) -> Result<f64, String> {
+            let mut value = self.unary()?;
+            loop {
+                if self.eat('*') {
+                    value *= self.unary()?;
+                } else if self.eat('/') {
+                    value /= self.unary()?;
+                } else if self.eat('%') {
+                    value %= self.unary()?;
+                } else {
+                    return Ok(value);
+                }
+            }
+        }
+
+        /// A factor, optionally negated.
+        fn unary(&mut self) -> Result<f64, String> {
+            if self.eat('-') {
+                Ok(-self.unary()?)
+            } else {
+                self.power()
+            }
+        }
+
+        /// A primary raised to a power; `2^3^2` is `2^(3^2)`.
+        fn power(&mut self) -> Result<f64, String> {
+            let base = self.primary()?;
+            if self.eat('^') {
+                Ok(base.powf(self.unary()?))
+            } else {
+                Ok(base)
+            }
+        }
+
+        /// A number, a parameter, a function call, or a parenthesized expression.
+        fn primary(&mut self) -> Result<f64, String> {
+            self.skip_whitespace();
+            match self.chars.peek().copied() {
+                Some('(') => {
+                    self.chars.next();
+                    let value = self.sum()?;
+                    if !self.eat(')') {
+                        return Err("missing ')'".to_string());
+                    }
+                    Ok(value)
+                },
+                Some(c) if c.is_ascii_digit() || c == '.' => {
+                    let mut number = String::new();
+                    while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                        number.push(c);
+                    }
+                    number.parse::<f64>().map_err(|_| format!("'{}' isn't a number", number))
+                },
+                Some(c) if c.is_alphabetic() || c == '_' => {
+                    let mut name = String::new();
+                    while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                        name.push(c);
+                    }
+                    if self.eat('(') {
+                        self.call(&name)
+                    } else {
+                        self.params.get(&name).copied().ok_or_else(|| format!("unknown parameter '{}'", name))
+                    }
+                },
+                Some(c) => Err(format!("unexpected '{}'", c)),
+                None => Err("the expression ended unexpectedly".to_string()),
+            }
+        }
+
+        /// The arguments (after the opening parenthesis) and result of a function call.
+        fn call(&mut self, name: &str) -> Result<f64, String> {
+            let mut args = vec![self.sum()?];
+            while self.eat(',') {
+                args.push(self.sum()?);
+            }
+            if !self.eat(')') {
+                return Err(format!("missing ')' after the arguments of '{}'", name));
+            }
+            match (name, args.as_slice()) {
+                ("abs", [x]) => Ok(x.abs()),
+                ("sqrt", [x]) => Ok(x.sqrt()),
+                ("floor", [x]) => Ok(x.floor()),
+                ("ceil", [x]) => Ok(x.ceil()),
+                ("round", [x]) => Ok(x.round()),
+                ("log2", [x]) => Ok(x.log2()),
+                ("log10", [x]) => Ok(x.log10()),
+                ("min", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |min, x| min.min(*x))),
+                ("max", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |max, x| max.max(*x))),
+                ("abs" | "sqrt" | "floor" | "ceil" | "round" | "log2" | "log10", _) => {
+                    Err(format!("'{}' takes 1 argument, but {} were given", name, args.len()))
+                },
+                _ => Err(format!("unknown function '{}'", name)),
+            }
+        }
+    }
+}
+
+mod gift {
+    //! Parser for Moodle's GIFT quiz format (https://docs.moodle.org/en/GIFT_format), so that
+    //! existing question banks can be studied without converting them by hand. Multiple choice
+    //! (including partial credit, which becomes multiple select), true/false, and short answer
+    //! questions are supported; matching and numeric questions are skipped.
+
+    /// Format markers that may precede question text, e.g., `[markdown]`
+    const FORMAT_MARKERS: [&str; 4] = ["[html]", "[markdown]", "[moodle]", "[plain]"];
+
+    /// A question parsed from a GIFT file, in terms of the exam's question types (`mc`, `ms`, `ue`)
+    #[derive(Debug)]
+    pub struct GiftQuestion {
+        pub q_type: &'static str,
+        pub prompt: String,
+        pub choices: Vec<String>,
+        pub answer: Vec<String>,
+        pub explanation: String,
+        /// The last part of the `$CATEGORY` in effect for the question, if any
+        pub category: Option<String>,
+    }
+
+    /// An answer from a question's answer block, e.g., `~%50%Paris#Feedback`
+    struct GiftAnswer {
+        text: String,
+        correct: bool,
+        partial: bool,
+        feedback: String,
+    }
+
+    /// Parses the contents of a GIFT file.
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<GiftQuestion>, Vec<String>)` - the parsed questions, and a description of each
+    ///   question that was skipped because it couldn't be understood or isn't supported.
+    pub fn parse(text: &str) -> (Vec<GiftQuestion>, Vec<String>) {
+        let mut questions: Vec<GiftQuestion> = Vec::new();
+        let mut skipped: Vec<String> = Vec::new();
+        let mut category: Option<String> = None;
+        for block in blocks(text) {
+            let mut block = block.as_str();
+            // A category applies to every question after it
+            if let Some(rest) = block.strip_prefix("$CATEGORY:") {
+                let (line, remainder) = rest.split_once('\n').unwrap_or((rest, ""));
+                category = line.trim().rsplit('/').next().map(str::trim).filter(|name| !name.is_empty()).map(str::to_string);
+                block = remainder.trim();
+                if block.is_empty() {
+                    continue;
+                }
+            }
+            match parse_question(block) {
+                Ok(Some(mut question)) => {
+                    question.category = category.clone();
+                    questions.push(question);
+                },
+                // Descriptions (text without an answer block) aren't questions
+                Ok(None) => {},
+                Err(reason) => skipped.push(format!("{} ({})", summarize(block), reason)),
+            }
+        }
+        (questions, skipped)
+    }
+
+    /// Helper function that splits the file into question blocks, which are separated by blank
+    /// lines; comment lines are dropped, and blank lines inside an answer block are kept.
+    fn blocks(text: &str) -> Vec<String> {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut depth: usize = 0;
+        for line in text.lines() {
+            if line.trim_start().starts_with("//") {
+                continue;
+            }
+            if line.trim().is_empty() && depth == 0 {
+                if !current.trim().is_empty() {
+                    blocks.push(current.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            for (_, c) in unescaped(line) {
+                match c {
+                    '{' => depth += 1,
+                    // A stray closing brace can't close anything, and mustn't keep every blank
+                    // line after it from separating questions
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {},
+                }
+            }
+            current.push_str(line);
+            current.push('\n');
+        }
+        if !current.trim().is_empty() {
+            blocks.push(current.trim().to_string());
+        }
+        blocks
+    }
+
+    /// Helper function that parses a single question block.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<GiftQuestion>, &str>` - the question, `None` if the block has no answer
+    ///   block, or the reason it can't be studied.
+    fn parse_question(block: &str) -> Result<Option<GiftQuestion>, &'static str> {
+        // The optional title (`::Title::`) isn't used
+        let block = match block.strip_prefix("::") {
+            Some(rest) => match find_unescaped(rest, "::") {
+                Some(end) => rest[end + 2..].trim_start(),
+                None => return Err("unterminated title"),
+            },
+            None => block,
+        };
+        let Some(open) = find_unescaped(block, "{") else { return Ok(None) };
+        let close = find_unescaped(&block[open..], "}").map(|close| open + close).ok_or("unterminated answer block")?;
+        let before = strip_format_marker(block[..open].trim());
+        let after = block[close + 1..].trim();
+        // Text after the answer block makes it a fill-in-the-blank question
+        let prompt = if after.is_empty() { unescape(before) } else { format!("{} _____ {}", unescape(before), unescape(after)) };
+        let inner = block[open + 1..close].trim();
+        let (inner, general_feedback) = match find_unescaped(inner, "####") {
+            Some(index) => (inner[..index].trim(), unescape(inner[index + 4..].trim())),
+            None => (inner, String::new()),
+        };
+        if inner.starts_with('#') {
+            return Err("numeric questions aren't supported");
+        }
+
+        // True/false questions, e.g., `{T}` or `{FALSE#feedback}`
+        let value = find_unescaped(inner, "#").map_or(inner, |index| &inner[..index]).trim();
+        let truth = match value.to_ascii_uppercase().as_str() {
+            "T" | "TRUE" => Some("True"),
+            "F" | "FALSE" => Some("False"),
+            _ => None,
+        };
+        if let Some(truth) = truth {
+            return Ok(Some(GiftQuestion {
+                q_type: "mc",
+                prompt,
+                choices: vec!["True".to_string(), "False".to_string()],
+                answer: vec![truth.to_string()],
+                explanation: general_feedback,
+                category: None,
+            }));
+        }
+
+        let answers = parse_answers(inner)?;
+        if answers.is_empty() {
+            return Err("no answers");
+        }
+        let correct: Vec<&GiftAnswer> = answers.iter().filter(|answer| answer.correct).collect();
+        let Some(first_correct) = correct.first() else { return Err("no correct answer") };
+        let explanation = if general_feedback.is_empty() { first_correct.feedback.clone() } else { general_feedback };
+        let answer: Vec<String> = correct.iter().map(|answer| answer.text.clone()).collect();
+        // Only correct answers (`=`) means that the answer is typed in
+        if answers.iter().all(|answer| answer.correct && !answer.partial) {
+            return Ok(Some(GiftQuestion { q_type: "ue", prompt, choices: Vec::new(), answer, explanation, category: None }));
+        }
+        let q_type = if correct.len() == 1 && !first_correct.partial { "mc" } else { "ms" };
+        let choices = answers.iter().map(|answer| answer.text.clone()).collect();
+        Ok(Some(GiftQuestion { q_type, prompt, choices, answer, explanation, category: None }))
+    }
+
+    /// Helper function that parses the answers of an answer block; each starts with `=` (correct)
+    /// or `~` (incorrect, unless given a positive `%weight%`), and may end with `#feedback`.
+    fn parse_answers(inner: &str) -> Result<Vec<GiftAnswer>, &'static str> {
+        // Byte offsets of the unescaped `=` and `~` that start each answer
+        let starts: Vec<usize> = unescaped(inner).filter(|(_, c)| *c == '=' || *c == '~').map(|(index, _)| index).collect();
+        if starts.first() != Some(&0) {
+            return Err("answers must start with '=' or '~'");
+        }
+        let mut answers: Vec<GiftAnswer> = Vec::new();
+        for (position, start) in starts.iter().enumerate() {
+            let end = starts.get(position + 1).copied().unwrap_or(inner.len());
+            let raw = inner[start + 1..end].trim();
+            if find_unescaped(raw, "->").is_some() {
+                return Err("matching questions aren't supported");
+            }
+            let (raw, weight) = match raw.strip_prefix('%') {
+                Some(rest) => {
+                    let (weight, rest) = rest.split_once('%').ok_or("unterminated answer weight")?;
+                    (rest, Some(weight.trim().parse::<f64>().map_err(|_| "invalid answer weight")?))
+                },
+                None => (raw, None),
+            };
+            let (text, feedback) = match find_unescaped(raw, "#") {
+                Some(index) => (&raw[..index], unescape(raw[index + 1..].trim())),
+                None => (raw, String::new()),
+            };
+            let is_equals = inner[*start..].starts_with('=');
+            answers.push(GiftAnswer {
+                text: unescape(text.trim()),
+                correct: weight.map_or(is_equals, |weight| weight > 0.0),
+                partial: weight.is_some_and(|weight| weight > 0.0 && weight < 100.0),
+                feedback,
+            });
+        }
+        Ok(answers)
+    }
+
+    /// Helper function that iterates over the characters of text that aren't escaped with a
+    /// backslash, along with their byte offsets.
+    fn unescaped(text: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+        let mut escaped = false;
+        text.char_indices().filter(move |(_, c)| {
+            let is_unescaped = !escaped && *c != '\\';
+            escaped = !escaped && *c == '\\';
+            is_unescaped
+        })
+    }
+
+    /// Helper function that finds the byte offset of the first unescaped occurrence of a pattern.
+    fn find_unescaped(text: &str, pattern: &str) -> Option<usize> {
+        unescaped(text).map(|(index, _)| index).find(|index| text[*index..].starts_with(pattern))
+    }
+
+    /// Helper function that removes a leading format marker (e.g., `[markdown]`) from text.
+    fn strip_format_marker(text: &str) -> &str {
+        FORMAT_MARKERS.iter().find_map(|marker| text.strip_prefix(marker)).unwrap_or(text).trim()
+    }
+
+    /// Helper function that replaces GIFT escape sequences (e.g., `\=`) with the characters they
+    /// stand for, and joins the lines of the text.
+    fn unescape(text: &str) -> String {
+        let mut unescaped = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match (c, c == '\\') {
+                (_, true) => match chars.next() {
+                    Some('n') => unescaped.push('\n'),
+                    Some(next) => unescaped.push(next),
+                    None => unescaped.push('\\'),
+                },
+                ('\n', false) => unescaped.push(' '),
+                _ => unescaped.push(c),
+            }
+        }
+        unescaped.trim().to_string()
+    }
+
+    /// Helper function that shortens a question block for a message about it.
+    fn summarize(block: &str) -> String {
+        let line = block.lines().next().unwrap_or_default().trim();
+        match line.char_indices().nth(60) {
+            Some((index, _)) => format!("{}...", &line[..index]),
+            None => line.to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Helper function that parses a file expected to hold a single question, without any
+        /// skipped blocks.
+        fn parse_one(text: &str) -> GiftQuestion {
+            let (mut questions, skipped) = parse(text);
+            assert!(skipped.is_empty(), "skipped: {:?}", skipped);
+            assert_eq!(questions.len(), 1);
+            questions.remove(0)
+        }
+
+        #[test]
+        fn parses_multiple_choice() {
+            let question = parse_one("::Capital:: What is the capital of France? {=Paris#Correct! ~London ~Berlin}");
+            assert_eq!(question.q_type, "mc");
+            assert_eq!(question.prompt, "What is the capital of France?");
+            assert_eq!(question.choices, vec!["Paris", "London", "Berlin"]);
+            assert_eq!(question.answer, vec!["Paris"]);
+            assert_eq!(question.explanation, "Correct!");
+        }
+
+        #[test]
+        fn parses_partial_credit_as_multiple_select() {
+            let question = parse_one("Which are web ports? {\n~%50%80\n~%50%443\n~%-100%22\n}");
+            assert_eq!(question.q_type, "ms");
+            assert_eq!(question.choices, vec!["80", "443", "22"]);
+            assert_eq!(question.answer, vec!["80", "443"]);
+        }
+
+        #[test]
+        fn parses_true_false() {
+            let question = parse_one("The sun is a star. {TRUE####It's the closest one}");
+            assert_eq!(question.q_type, "mc");
+            assert_eq!(question.choices, vec!["True", "False"]);
+            assert_eq!(question.answer, vec!["True"]);
+            assert_eq!(question.explanation, "It's the closest one");
+            assert_eq!(parse_one("The moon is a star. {F}").answer, vec!["False"]);
+        }
+
+        #[test]
+        fn parses_short_answer() {
+            let question = parse_one("Two plus two equals {=four =4}.");
+            assert_eq!(question.q_type, "ue");
+            assert_eq!(question.prompt, "Two plus two equals _____ .");
+            assert!(question.choices.is_empty());
+            assert_eq!(question.answer, vec!["four", "4"]);
+        }
+
+        #[test]
+        fn unescapes_special_characters() {
+            let question = parse_one("What does \\= mean in \\{GIFT\\}? {=An escaped \\= or \\~ ~A brace \\}}");
+            assert_eq!(question.prompt, "What does = mean in {GIFT}?");
+            assert_eq!(question.choices, vec!["An escaped = or ~", "A brace }"]);
+            assert_eq!(question.answer, vec!["An escaped = or ~"]);
+        }
+
+        #[test]
+        fn drops_comments_and_keeps_categories() {
+            let text = "// A comment {=not a question}\n$CATEGORY: $course$/Networking/Ports\n\n// Another comment\nSSH port? {=22 ~80}\n";
+            let question = parse_one(text);
+            assert_eq!(question.prompt, "SSH port?");
+            assert_eq!(question.category.as_deref(), Some("Ports"));
+        }
+
+        #[test]
+        fn stray_closing_brace_does_not_merge_the_following_questions() {
+            let text = "A stray } brace in a description\n\nFirst? {=1 ~2}\n\nSecond? {T}\n";
+            let (questions, skipped) = parse(text);
+            assert!(skipped.is_empty(), "skipped: {:?}", skipped);
+            let prompts: Vec<&str> = questions.iter().map(|question| question.prompt.as_str()).collect();
+            assert_eq!(prompts, vec!["First?", "Second?"]);
+        }
+
+        #[test]
+        fn skips_unsupported_questions() {
+            let (questions, skipped) = parse("Pi to two places? {#3.14}\n\nMatch them {=a -> 1 =b -> 2}\n");
+            assert!(questions.is_empty());
+            assert_eq!(skipped.len(), 2);
+        }
+    }
+}
+
+mod menu {
+    //! Arrow-key selection from a list of choices, drawn in place in the terminal. The terminal is
+    //! switched to raw mode while a choice is being selected, and always restored afterward.
+
+    use std::io::{self, Read, Write};
+
+    /// How the user finished selecting from the menu
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Selection {
+        /// The choice at this index was selected with Enter
+        Chosen(usize),
+        /// Escape was pressed to type the answer (or a command) instead
+        Typed,
+        /// Ctrl+C was pressed
+        Interrupted,
+    }
+
+    /// Keys that the menu responds to
+    enum Key {
+        Up,
+        Down,
+        Enter,
+        Escape,
+        Interrupt,
+        Other,
+    }
+
+    /// Lets the user pick one of the given labels with the up and down arrow keys and Enter; the
+    /// highlighted label is redrawn on each key press. The labels are drawn starting at the current
+    /// line, and remain on screen afterward.
+    pub fn select(labels: &[String], highlight: &str, reset: &str) -> io::Result<Selection> {
+        let _raw_mode = raw::RawMode::enable()?;
+        let mut stdout = io::stdout();
+        let mut selected = 0;
+        draw(&mut stdout, labels, selected, highlight, reset, false)?;
+        loop {
+            match read_key()? {
+                Key::Up => selected = (selected + labels.len() - 1) % labels.len(),
+                Key::Down => selected = (selected + 1) % labels.len(),
+                Key::Enter => return Ok(Selection::Chosen(selected)),
+                Key::Escape => return Ok(Selection::Typed),
+                Key::Interrupt => return Ok(Selection::Interrupted),
+                Key::Other => continue,
+            }
+            draw(&mut stdout, labels, selected, highlight, reset, true)?;
+        }
+    }
+
+    /// Helper function that draws the labels, marking the selected one; when `redraw` is set, the
+    /// cursor is first moved back up to overwrite the previous drawing. Raw mode doesn't translate
+    /// line feeds, so each line ends with an explicit carriage return.
+    fn draw(stdout: &mut io::Stdout, labels: &[String], selected: usize, highlight: &str, reset: &str, redraw: bool) -> io::Result<()> {
+        if redraw {
+            write!(stdout, "\x1b[{}A", labels.len())?;
+        }
+        for (index, label) in labels.iter().enumerate() {
+            if index == selected {
+                write!(stdout, "\r\x1b[2K\t{}> {}{}\r\n", highlight, label, reset)?;
+            } else {
+                write!(stdout, "\r\x1b[2K\t  {}\r\n", label)?;
+            }
+        }
+        stdout.flush()
+    }
+
+    /// Helper function that reads a single key press; arrow keys arrive as a single escape
+    /// sequence, while a lone escape byte is the Escape key itself.
+    fn read_key() -> io::Result<Key> {
+        let mut buffer = [0u8; 8];
+        let num_read = io::stdin().read(&mut buffer)?;
+        Ok(match &buffer[..num_read] {
+            // End of input can't select anything, so it's treated like typing the answer instead
+            [] | [0x1b] => Key::Escape,
+            [0x1b, b'[' | b'O', b'A'] => Key::Up,
+            [0x1b, b'[' | b'O', b'B'] => Key::Down,
+            [b'\r' | b'\n'] => Key::Enter,
+            [0x03] => Key::Interrupt,
+            _ => Key::Other,
+        })
+    }
+
+    #[cfg(unix)]
+    mod raw {
+        use std::io;
+        use std::os::fd::AsFd;
+        use rustix::termios::{self, OptionalActions, Termios};
+
+        /// Keeps the terminal in raw mode while it exists; the original settings are restored
+        /// when it's dropped, including while unwinding from a panic.
+        pub struct RawMode {
+            original: Termios,
+        }
+
+        impl RawMode {
+            pub fn enable() -> io::Result<Self> {
+                let stdin = io::stdin();
+                let original = termios::tcgetattr(stdin.as_fd())?;
+                let mut raw = original.clone();
+                raw.make_raw();
+                termios::tcsetattr(stdin.as_fd(), OptionalActions::Now, &raw)?;
+                Ok(RawMode { original })
+            }
+        }
+
+        impl Drop for RawMode {
+            fn drop(&mut self) {
+                let _ = termios::tcsetattr(io::stdin().as_fd(), OptionalActions::Now, &self.original);
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    mod raw {
+        use std::io;
+
+        /// Raw mode is only supported on Unix-like systems
+        pub struct RawMode;
+
+        impl RawMode {
+            pub fn enable() -> io::Result<Self> {
+                Err(io::Error::new(io::ErrorKind::Unsupported, "arrow-key selection isn't supported on this platform"))
+            }
+        }
+    }
+}
+
+mod notes {
+    use std::fs::{self, OpenOptions};
+    use std::io::{self, Write};
+    use std::path::PathBuf;
+    use chrono::Local;
+    use crate::exam::data_path;
+
+    /// Name of the append-only Markdown file (within the `assets` directory) that stores notes
+    const NOTES_FILE: &str = "notes.md";
+
+    /// Appends a note about a question to the notes file, prefixed with the current time and
+    /// the question's ID; the file (and `assets` directory) is created if it doesn't exist yet.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<PathBuf>` - the path of the notes file the note was written to.
+    pub fn append(question_id: &str, text: &str) -> io::Result<PathBuf> {
+        let path = data_path(NOTES_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "- [{}] `{}`: {}", Local::now().format("%Y-%m-%d %H:%M"), question_id, text)?;
+        Ok(path)
+    }
+
+    /// Prints every note that has been accumulated in the notes file.
+    pub fn display() -> io::Result<()> {
+        let path = data_path(NOTES_FILE)?;
+        match fs::read_to_string(&path) {
+            Ok(contents) if !contents.trim().is_empty() => print!("{}", contents),
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => println!("No notes have been taken yet; enter 'note' at any answer prompt to add one."),
+        }
+        Ok(())
+    }
+}
+
+mod history {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::fs;
+    use std::io;
+    use chrono::{DateTime, FixedOffset, Local};
+    use serde::{Deserialize, Serialize};
+    use crate::exam::{color, data_path, load_data, Role};
+
+    /// Name of the JSON file (within the `assets` directory) that stores the study history
+    pub const HISTORY_FILE: &str = "history.json";
+
+    /// Default weight of each newer session in the recent accuracy estimate
+    const DEFAULT_DECAY: f64 = 0.3;
+
+    /// The kind of session that was run; lets casual practice be separated from mock exams
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum SessionMode {
+        /// A regular study session with immediate feedback
+        #[default]
+        Practice,
+        /// A timed mock exam run with `--simulate`
+        Simulate,
+        /// A race against the clock run with `--rapid-fire`
+        #[serde(rename = "rapid-fire")]
+        RapidFire,
+        /// The scored test phase of a `--learn-then-test` session
+        Learn,
+    }
+
+    /// Summary of a single completed study session
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct SessionRecord {
+        /// Name of the exam that was studied
+        pub exam: String,
+        /// The kind of session; records written before modes were tracked are practice sessions
+        #[serde(default)]
+        pub mode: SessionMode,
+        /// When the session finished, in RFC 3339 format
+        pub timestamp: String,
+        /// Number of questions that were answered correctly
+        pub correct: usize,
+        /// Number of questions that were answered
+        pub answered: usize,
+        /// Results of the individual questions; empty for records written before these were tracked
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub questions: Vec<QuestionResult>,
+    }
+
+    /// Outcome of a single question within a session
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct QuestionResult {
+        /// ID of the question (see `Question::id`)
+        pub id: String,
+        /// Whether the question was answered correctly
+        pub correct: bool,
+        /// How long the user took to answer, in seconds
+        pub seconds: f64,
+        /// Type of the question as it was asked (`mc`, `ms`, or `ue`); absent from results recorded
+        /// before types were tracked
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub q_type: Option<String>,
+    }
+
+    /// Loads every session record from the history file; a missing file means that no sessions
+    /// have been recorded yet.
+    pub fn load() -> io::Result<Vec<SessionRecord>> {
+        load_data(HISTORY_FILE)
+    }
+
+    /// Appends a record of a finished session to the history file, creating the file (and the
+    /// `assets` directory) if needed.
+    pub fn record(exam: &str, mode: SessionMode, correct: usize, answered: usize, questions: Vec<QuestionResult>) -> io::Result<()> {
+        let mut records = load()?;
+        records.push(SessionRecord {
+            exam: exam.to_string(),
+            mode,
+            timestamp: Local::now().to_rfc3339(),
+            correct,
+            answered,
+            questions,
+        });
+        let path = data_path(HISTORY_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&records)?)
+    }
+
+    /// Counts how many times each question of the given exam was answered incorrectly in previous
+    /// sessions, keyed by question ID.
+    pub fn misses(exam: &str) -> io::Result<HashMap<String, usize>> {
+        let mut misses: HashMap<String, usize> = HashMap::new();
+        for record in load()?.iter().filter(|record| record.exam == exam) {
+            for result in record.questions.iter().filter(|result| !result.correct) {
+                *misses.entry(result.id.clone()).or_default() += 1;
+            }
+        }
+        Ok(misses)
+    }
+
+    /// Counts how many questions were answered today (in the local time zone), across every exam
+    /// and session mode.
+    pub fn answered_today() -> io::Result<usize> {
+        let today = Local::now().date_naive();
+        Ok(load()?
+            .iter()
+            .filter(|record| DateTime::parse_from_rfc3339(&record.timestamp).is_ok_and(|time| time.with_timezone(&Local).date_naive() == today))
+            .map(|record| record.answered)
+            .sum())
+    }
+
+    /// Finds the IDs of the questions that were answered incorrectly in the most recent session of
+    /// the given exam; empty if the exam was never studied, or its last session had no misses.
+    pub fn last_missed(exam: &str) -> io::Result<HashSet<String>> {
+        Ok(load()?
+            .iter()
+            .rev()
+            .find(|record| record.exam == exam)
+            .map(|record| record.questions.iter().filter(|result| !result.correct).map(|result| result.id.clone()).collect())
+            .unwrap_or_default())
+    }
+
+    /// Counts how many times in a row each question of the given exam was answered correctly,
+    /// ending with its most recent answer, keyed by question ID; a question whose most recent
+    /// answer was incorrect has a streak of 0.
+    pub fn streaks(exam: &str) -> io::Result<HashMap<String, usize>> {
+        let mut streaks: HashMap<String, usize> = HashMap::new();
+        // Records are appended as sessions finish, so they're already in chronological order
+        for record in load()?.iter().filter(|record| record.exam == exam) {
+            for result in record.questions.iter() {
+                let streak = streaks.entry(result.id.clone()).or_default();
+                *streak = if result.correct { *streak + 1 } else { 0 };
+            }
+        }
+        Ok(streaks)
+    }
+
+    /// Finds when each question of the given exam was last answered in previous sessions, keyed
+    /// by question ID; questions that were never answered have no entry.
+    pub fn last_seen(exam: &str) -> io::Result<HashMap<String, DateTime<FixedOffset>>> {
+        let mut last_seen: HashMap<String, DateTime<FixedOffset>> = HashMap::new();
+        for record in load()?.iter().filter(|record| record.exam == exam) {
+            // Records with an unreadable timestamp can't say how long ago their questions were seen
+            let Ok(time) = DateTime::parse_from_rfc3339(&record.timestamp) else { continue };
+            for result in record.questions.iter() {
+                let seen = last_seen.entry(result.id.clone()).or_insert(time);
+                *seen = (*seen).max(time);
+            }
+        }
+        Ok(last_seen)
+    }
+
+    /// Prints the number of sessions, overall accuracy, and most recent session for each exam
+    /// in the study history, along with the accuracy of each session mode and an estimate of
+    /// recent accuracy that weighs newer sessions more.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - when provided, only sessions of this mode are included.
+    /// * `decay` - weight (between 0 and 1) of each newer session in the recent estimate; the
+    ///   default is used when not provided.
+    pub fn display_stats(mode: Option<SessionMode>, decay: Option<f64>) -> io::Result<()> {
+        let records = load()?;
+        let mut by_exam: BTreeMap<&str, Vec<&SessionRecord>> = BTreeMap::new();
+        for record in records.iter().filter(|record| mode.is_none_or(|mode| record.mode == mode)) {
+            by_exam.entry(record.exam.as_str()).or_default().push(record);
+        }
+        if by_exam.is_empty() {
+            println!("No study sessions have been recorded yet.");
+            return Ok(());
+        }
+        for (exam, sessions) in by_exam.iter() {
+            let (correct, answered) = totals(sessions.iter().copied());
+            println!("\n{}", exam);
+            println!("\tSessions: {}", sessions.len());
+            println!("\tQuestions answered: {} ({} correct)", answered, correct);
+            println!("\tAccuracy: {:.1}%", accuracy(correct, answered));
+            if let Some((average, recent)) = session_trend(sessions, decay.unwrap_or(DEFAULT_DECAY)) {
+                println!("\tAverage session accuracy: {:.1}%", average);
+                println!("\tRecent accuracy (weighted toward newer sessions): {:.1}%", recent);
+            }
+            let mut by_mode: BTreeMap<SessionMode, Vec<&SessionRecord>> = BTreeMap::new();
+            for session in sessions.iter() {
+                by_mode.entry(session.mode).or_default().push(session);
+            }
+            for (mode, mode_sessions) in by_mode.iter() {
+                let (correct, answered) = totals(mode_sessions.iter().copied());
+                println!("\t\t{:?}: {} sessions, {:.1}%", mode, mode_sessions.len(), accuracy(correct, answered));
+            }
+            if let Some(last) = sessions.last() {
+                println!("\tLast studied: {}", session_time(last));
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the two most recent sessions of the exam studied last side by side: the overall
+    /// accuracy and the accuracy for each question type, along with how much each changed.
+    /// Improvements are highlighted in green, and regressions in red.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - when provided, only sessions of this mode are compared.
+    pub fn display_comparison(mode: Option<SessionMode>) -> io::Result<()> {
+        let records = load()?;
+        let sessions: Vec<&SessionRecord> = records.iter().filter(|record| mode.is_none_or(|mode| record.mode == mode)).collect();
+        let Some(latest) = sessions.last() else {
+            println!("No study sessions have been recorded yet.");
+            return Ok(());
+        };
+        let Some(previous) = sessions.iter().rev().skip(1).find(|session| session.exam == latest.exam) else {
+            println!("Only one session of {} has been recorded; study it again to compare sessions.", latest.exam);
+            return Ok(());
+        };
+
+        println!("\nComparing the last two sessions of {}", latest.exam);
+        println!("\t{:<10}{:>22}{:>22}{:>10}", "", session_time(previous), session_time(latest), "Change");
+        print_comparison("Overall", Some((previous.correct, previous.answered)), Some((latest.correct, latest.answered)));
+        let (previous_types, latest_types) = (by_type(previous), by_type(latest));
+        let q_types: BTreeSet<&str> = previous_types.keys().chain(latest_types.keys()).copied().collect();
+        for q_type in q_types {
+            print_comparison(q_type, previous_types.get(q_type).copied(), latest_types.get(q_type).copied());
+        }
+        Ok(())
+    }
+
+    /// Tallies the (correct, answered) questions of each question type in a session; results
+    /// recorded without a type are left out.
+    fn by_type(session: &SessionRecord) -> BTreeMap<&str, (usize, usize)> {
+        let mut tallies: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for result in session.questions.iter() {
+            if let Some(q_type) = result.q_type.as_deref() {
+                let tally = tallies.entry(q_type).or_default();
+                tally.0 += usize::from(result.correct);
+                tally.1 += 1;
+            }
+        }
+        tallies
+    }
+
+    /// Prints a row of the session comparison; a session without any questions of the row's
+    /// kind is shown as `-`, and the change is only shown when both sessions have some.
+    fn print_comparison(label: &str, previous: Option<(usize, usize)>, latest: Option<(usize, usize)>) {
+        let cell = |tally: Option<(usize, usize)>| match tally {
+            Some((correct, answered)) if answered > 0 => format!("{}/{} ({:.1}%)", correct, answered, accuracy(correct, answered)),
+            _ => "-".to_string(),
+        };
+        let change = match (previous, latest) {
+            (Some((previous_correct, previous_answered)), Some((latest_correct, latest_answered))) if previous_answered > 0 && latest_answered > 0 => {
+                let delta = accuracy(latest_correct, latest_answered) - accuracy(previous_correct, previous_answered);
+                // Padded before coloring, since the escape codes don't take up any columns
+                let text = format!("{:>+9.1}%", delta);
+                if delta > 0.0 {
+                    format!("{}{}{}", color(Role::Success), text, color(Role::Reset))
+                } else if delta < 0.0 {
+                    format!("{}{}{}", color(Role::Error), text, color(Role::Reset))
+                } else {
+                    text
+                }
+            },
+            _ => format!("{:>10}", "-"),
+        };
+        println!("\t{:<10}{:>22}{:>22}{}", label, cell(previous), cell(latest), change);
+    }
+
+    /// When a session finished, formatted for display; the raw timestamp is used if it can't be read.
+    fn session_time(session: &SessionRecord) -> String {
+        DateTime::parse_from_rfc3339(&session.timestamp)
+            .map_or(session.timestamp.clone(), |time| time.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    /// Computes the simple average of the accuracy of each session, along with an exponential
+    /// moving average where each newer session has a weight of `decay`; sessions are in the order
+    /// they were recorded. Returns `None` if no session answered any questions.
+    fn session_trend(sessions: &[&SessionRecord], decay: f64) -> Option<(f64, f64)> {
+        let accuracies: Vec<f64> = sessions
+            .iter()
+            .filter(|session| session.answered > 0)
+            .map(|session| accuracy(session.correct, session.answered))
+            .collect();
+        let (first, rest) = accuracies.split_first()?;
+        let average = accuracies.iter().sum::<f64>() / accuracies.len() as f64;
+        let recent = rest.iter().fold(*first, |ema, accuracy| decay * accuracy + (1.0 - decay) * ema);
+        Some((average, recent))
+    }
+
+    /// Sums the (correct, answered) question counts of the given sessions.
+    fn totals<'a, I: Iterator<Item = &'a SessionRecord>>(sessions: I) -> (usize, usize) {
+        sessions.fold((0, 0), |(correct, answered), session| (correct + session.correct, answered + session.answered))
+    }
+
+    /// Percentage of answered questions that were correct.
+    fn accuracy(correct: usize, answered: usize) -> f64 {
+        if answered > 0 { 100.0 * correct as f64 / answered as f64 } else { 0.0 }
+    }
+}
+
+mod bookmarks {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fs;
+    use std::io;
+    use crate::exam::{data_path, load_data};
+
+    /// Name of the JSON file (within the `assets` directory) that stores bookmarked questions
+    pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+    /// IDs of the bookmarked questions, keyed by the name of the exam they belong to
+    pub type Bookmarks = BTreeMap<String, BTreeSet<String>>;
+
+    /// Loads every bookmark from the bookmarks file; a missing file means that nothing has been
+    /// bookmarked yet.
+    pub fn load() -> io::Result<Bookmarks> {
+        load_data(BOOKMARKS_FILE)
+    }
+
+    /// Bookmarks a question of the given exam, creating the file (and the `assets` directory) if
+    /// needed.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<bool>` - whether the question was newly bookmarked; `false` if it already was.
+    pub fn add(exam: &str, question_id: &str) -> io::Result<bool> {
+        let mut bookmarks = load()?;
+        if !bookmarks.entry(exam.to_string()).or_default().insert(question_id.to_string()) {
+            return Ok(false);
+        }
+        let path = data_path(BOOKMARKS_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&bookmarks)?)?;
+        Ok(true)
+    }
+}
+
+mod reports {
+    use std::fs;
+    use std::io;
+    use std::path::PathBuf;
+    use chrono::{DateTime, Local};
+    use serde::{Deserialize, Serialize};
+    use crate::exam::{data_path, load_data, Question};
+
+    /// Name of the JSON file (within the `assets` directory) that stores questions reported as broken
+    pub const REPORTS_FILE: &str = "reports.json";
+
+    /// A question that was reported as broken (e.g., it has the wrong answer key) while studying
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct Report {
+        /// Name of the exam the question belongs to
+        pub exam: String,
+        /// ID of the question (see `Question::id`)
+        pub question_id: String,
+        /// The question's prompt, so the report can be understood without looking up the ID
+        pub prompt: String,
+        /// Optional description of the problem
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        pub note: String,
+        /// When the question was reported, in RFC 3339 format
+        pub timestamp: String,
+    }
+
+    /// Loads every report from the reports file; a missing file means that nothing has been
+    /// reported yet.
+    pub fn load() -> io::Result<Vec<Report>> {
+        load_data(REPORTS_FILE)
+    }
+
+    /// Reports a question of the given exam as broken, creating the file (and the `assets`
+    /// directory) if needed.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<PathBuf>` - the path of the reports file the report was written to.
+    pub fn add(exam: &str, question: &Question, note: &str) -> io::Result<PathBuf> {
+        let mut reports = load()?;
+        reports.push(Report {
+            exam: exam.to_string(),
+            question_id: question.id(),
+            prompt: question.prompt().to_string(),
+            note: note.to_string(),
+            timestamp: Local::now().to_rfc3339(),
+        });
+        let path = data_path(REPORTS_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(&reports)?)?;
+        Ok(path)
+    }
+
+    /// Prints every question that has been reported as broken, grouped by exam.
+    pub fn display() -> io::Result<()> {
+        let mut reports = load()?;
+        if reports.is_empty() {
+            println!("No questions have been reported yet; enter 'broken' at any answer prompt to report one.");
+            return Ok(());
+        }
+        // Stable sort, so reports of the same exam stay in the order they were made
+        reports.sort_by(|a, b| a.exam.cmp(&b.exam));
+        let mut current_exam: Option<&str> = None;
+        for report in reports.iter() {
+            if current_exam != Some(report.exam.as_str()) {
+                println!("\n{}", report.exam);
+                current_exam = Some(report.exam.as_str());
+            }
+            let when = DateTime::parse_from_rfc3339(&report.timestamp)
+                .map_or(report.timestamp.clone(), |time| time.format("%Y-%m-%d %H:%M").to_string());
+            println!("\t- [{}] `{}`: {}", when, report.question_id, report.prompt);
+            if !report.note.is_empty() {
+                println!("\t\t{}", report.note);
+            }
+        }
+        Ok(())
+    }
+}
+
+mod practice_sets {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::io;
+    use std::path::PathBuf;
+    use serde::{Deserialize, Serialize};
+    use crate::exam::{data_path, load_data};
+
+    /// Name of the JSON file (within the `assets` directory) that stores saved practice sets
+    pub const PRACTICE_SETS_FILE: &str = "practice_sets.json";
+
+    /// A saved sequence of questions that can be studied again in the same order
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct PracticeSet {
+        /// Name of the exam the questions belong to
+        pub exam: String,
+        /// Seed of the session's random number generator, so that choices are shuffled the same way
+        pub seed: u64,
+        /// IDs of the questions (see `Question::id`), in the order they were asked
+        pub questions: Vec<String>,
+    }
+
+    /// Loads every practice set, keyed by name; a missing file means that none have been saved yet.
+    pub fn load() -> io::Result<BTreeMap<String, PracticeSet>> {
+        load_data(PRACTICE_SETS_FILE)
+    }
+
+    /// Saves a practice set under the given name, replacing any set with the same name and
+    /// creating the file (and the `assets` directory) if needed.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<PathBuf>` - the path of the file the practice set was written to.
+    pub fn save(name: &str, set: PracticeSet) -> io::Result<PathBuf> {
+        let mut sets = load()?;
+        sets.insert(name.to_string(), set);
+        let path = data_path(PRACTICE_SETS_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(&sets)?)?;
+        Ok(path)
+    }
+}
+
+mod recent {
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use crate::exam::{data_path, load_data};
+
+    /// Name of the JSON file (within the `assets` directory) that remembers the last exam studied
+    pub const RECENT_FILE: &str = "recent.json";
+
+    /// Loads the path of the exam file that was last selected, if any.
+    pub fn load() -> io::Result<Option<PathBuf>> {
+        load_data(RECENT_FILE)
+    }
+
+    /// Remembers the path of the exam file that was selected, creating the file (and the `assets`
+    /// directory) if needed; relative paths are stored as absolute paths.
+    pub fn save(path: &Path) -> io::Result<()> {
+        let path = fs::canonicalize(path)?;
+        let recent_path = data_path(RECENT_FILE)?;
+        if let Some(parent) = recent_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(recent_path, serde_json::to_string_pretty(&path)?)
+    }
+}
+
+mod attempts {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+    use chrono::Local;
+    use serde::{Deserialize, Serialize};
+    use crate::exam::data_path;
+
+    /// Name of the file (within the `assets` directory) that logs every answered question, one
+    /// JSON object per line so that each attempt can be appended without rewriting the file
+    pub const ATTEMPTS_FILE: &str = "attempts.jsonl";
+
+    /// A single answered question
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct Attempt {
+        /// ID of the run (study session) the question was answered in; increases with each run
+        pub run: u64,
+        /// Name of the exam the question belongs to
+        pub exam: String,
+        /// ID of the question (see `Question::id`)
+        pub question: String,
+        /// When the question was answered, in RFC 3339 format
+        pub timestamp: String,
+        /// The selected choice(s), or the entered text for user entry questions
+        pub given: Vec<String>,
+        /// The question's correct answer(s)
+        pub expected: Vec<String>,
+        /// Whether the answer was correct
+        pub correct: bool,
+    }
+
+    /// Picks the ID of a new run: one more than the highest run ID logged so far, or 1 if nothing
+    /// was logged yet. Lines that can't be parsed (e.g., after a partial write) are skipped.
+    pub fn next_run() -> io::Result<u64> {
+        let file = match File::open(data_path(ATTEMPTS_FILE)?) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(1),
+            Err(e) => return Err(e),
+        };
+        let mut last_run = 0;
+        for line in BufReader::new(file).lines() {
+            if let Ok(attempt) = serde_json::from_str::<Attempt>(&line?) {
+                last_run = last_run.max(attempt.run);
+            }
+        }
+        Ok(last_run + 1)
+    }
+
+    /// Appends an attempt to the log, creating the file (and the `assets` directory) if needed;
+    /// each attempt is written as a single line in one write, so earlier attempts are never
+    /// rewritten.
+    pub fn append(run: u64, exam: &str, question_id: &str, given: &[String], expected: &[String], correct: bool) -> io::Result<()> {
+        let attempt = Attempt {
+            run,
+            exam: exam.to_string(),
+            question: question_id.to_string(),
+            timestamp: Local::now().to_rfc3339(),
+            given: given.to_vec(),
+            expected: expected.to_vec(),
+            correct,
+        };
+        let path = data_path(ATTEMPTS_FILE)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(format!("{}\n", serde_json::to_string(&attempt)?).as_bytes())
+    }
+}
+
+mod settings {
+    use std::path::PathBuf;
+    use crate::exam::{Difficulty, Theme};
+    use crate::history::SessionMode;
+
+    /// Options provided on the command line that change how the program acquires and studies an
+    /// exam.
+    #[derive(Debug, Default, Clone)]
+    pub struct Settings {
+        /// The top-level command to run
+        pub command: Command,
+        /// Print the help message for the command instead of running it
+        pub help: bool,
+        /// Exam files to operate on instead of selecting one interactively
+        pub files: Vec<PathBuf>,
+        /// List the exams in this directory instead of studying one
+        pub list: Option<PathBuf>,
+        /// URL of a JSON-formatted exam to download instead of searching a local directory
+        pub url: Option<String>,
+        /// How many of a question's accepted answers are revealed after an incorrect answer
+        pub reveal: Reveal,
+        /// Only study questions of this section
+        pub section: Option<String>,
+        /// Only study questions with a reference containing this text
+        pub reference: Option<String>,
+        /// Study a random number of questions within this inclusive range instead of asking
+        pub count_range: Option<(usize, usize)>,
+        /// The fewest questions a study session may have
+        pub min_count: Option<usize>,
+        /// Study fewer questions than `min_count` after a warning, instead of refusing
+        pub allow_short: bool,
+        /// Template for revealing the correct answers, where `{answers}` is replaced by the answers
+        pub reveal_template: Option<String>,
+        /// Only study questions with this difficulty
+        pub difficulty: Option<Difficulty>,
+        /// Run a timed mock exam instead of a regular study session
+        pub simulate: bool,
+        /// Search the default `assets` directory without asking, and study its only exam file
+        pub use_default_dir: bool,
+        /// Study an exam file picked at random from the directory instead of asking for one
+        pub random: bool,
+        /// Keep reshuffling and asking the questions until the user quits
+        pub endless: bool,
+        /// Study the questions with feedback first, then test them again without it; only the test
+        /// is scored
+        pub learn_then_test: bool,
+        /// Answer multiple choice and multiple select questions by typing an answer instead
+        pub recall: bool,
+        /// Race to answer as many questions as possible within this many seconds
+        pub rapid_fire: Option<u64>,
+        /// Display choices in alphabetical order instead of shuffling them
+        pub sorted: bool,
+        /// Display short choices side by side in columns when they fit in the terminal
+        pub columns: bool,
+        /// Select the answers to multiple choice questions with the arrow keys
+        pub arrow_keys: bool,
+        /// Print explanations and references without labels, blank lines, or pauses
+        pub compact: bool,
+        /// Don't display scores during or after the session; they're still recorded to the history
+        pub hide_score: bool,
+        /// Copy the session's output to a timestamped transcript file
+        pub transcript: bool,
+        /// Don't print the logo (or banner) when the session starts
+        pub no_logo: bool,
+        /// Ring the terminal bell after each answer (once if correct, twice if incorrect)
+        pub bell: bool,
+        /// Milliseconds to pause after the feedback to a correct answer, replacing the default pauses
+        pub correct_delay: Option<u64>,
+        /// Milliseconds to pause after the feedback to an incorrect answer, replacing the default pauses
+        pub incorrect_delay: Option<u64>,
+        /// Phrases to pick from after a correct answer, in place of the exam's or the default
+        pub praise: Vec<String>,
+        /// Phrases to pick from after an incorrect answer, in place of the exam's or the default
+        pub consolation: Vec<String>,
+        /// End the study session once this many questions have been answered incorrectly
+        pub lives: Option<usize>,
+        /// Number of questions to answer each day; sessions that end short of it say how many remain
+        pub daily_goal: Option<usize>,
+        /// Only study questions that have been bookmarked
+        pub bookmarked: bool,
+        /// Only display this question (1-based, ordered by ID) of the exam file
+        pub question: Option<usize>,
+        /// Grade the answers in this response file instead of asking questions
+        pub answers: Option<PathBuf>,
+        /// Collect answers to every question, without feedback, into this response file
+        pub collect: Option<PathBuf>,
+        /// Print the answer key of the exam instead of studying it
+        pub key: bool,
+        /// Show how many choices a multiple select question expects
+        pub show_select_count: bool,
+        /// Require answers to user entry questions to be entered twice
+        pub confirm_ue: bool,
+        /// Number of tries at a user entry question before its answer is revealed
+        pub ue_attempts: Option<usize>,
+        /// Show an increasingly revealing hint after each wrong try at a user entry question
+        pub attempt_hints: bool,
+        /// Ask the user to explain each answer, and compare it against the explanation
+        pub teach_back: bool,
+        /// Show a letter grade in the summary, using these minimum percentages for an A, B, C, and D
+        pub grade_cutoffs: Option<[f64; 4]>,
+        /// Points deducted for each incorrect answer (negative marking)
+        pub penalty: Option<f64>,
+        /// Points deducted for each hint revealed at a user entry question
+        pub hint_penalty: Option<f64>,
+        /// Let the score under negative marking drop below zero
+        pub allow_negative: bool,
+        /// How the questions of a study session are picked
+        pub strategy: Strategy,
+        /// Start the session with this many of the easiest questions, from easiest to hardest
+        pub warmup: Option<usize>,
+        /// Only study questions that weren't answered in previous sessions
+        pub new_only: bool,
+        /// Combine filters in an interactive menu before studying
+        pub filter_builder: bool,
+        /// Leave out questions that were answered correctly enough times in a row
+        pub skip_mastered: bool,
+        /// Number of correct answers in a row after which `skip_mastered` leaves a question out
+        pub mastery_streak: Option<usize>,
+        /// Study the questions of this saved practice set, in their saved order
+        pub practice_set: Option<String>,
+        /// Bookmark questions once they have been missed more than this many times
+        pub flag_misses: Option<usize>,
+        /// Bookmark questions that take longer than this many seconds to answer
+        pub flag_seconds: Option<u64>,
+        /// Only include sessions of this mode in the statistics
+        pub mode: Option<SessionMode>,
+        /// Weight of each newer session in the recent accuracy estimate of the statistics
+        pub decay: Option<f64>,
+        /// Compare the two most recent sessions of an exam instead of displaying the statistics
+        pub compare: bool,
+        /// Color palette used for colored output
+        pub theme: Theme,
+    }
+
+    /// The top-level commands (subcommands) the program can run
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum Command {
+        /// Study an exam; the default when no command is given
+        #[default]
+        Study,
+        /// Review the notes taken during previous sessions
+        Review,
+        /// List the questions that were reported as broken
+        Reports,
+        /// Validate exam files without studying them
+        Check,
+        /// Display statistics from previous study sessions
+        Stats,
+        /// Interactively create or extend an exam file
+        Author,
+    }
+
+    /// How the questions of a study session are picked from the exam
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum Strategy {
+        /// Questions are picked at random
+        #[default]
+        Random,
+        /// Questions that haven't been answered in the longest time are picked first
+        Spaced,
+        /// Questions are picked at random, but those of the same group are kept together
+        Grouped,
+    }
+
+    /// Controls which of a question's accepted answers are shown after an incorrect answer
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    pub enum Reveal {
+        /// Every accepted answer is shown
+        #[default]
+        All,
+        /// Only the first (canonical) answer is shown
+        Primary,
+    }
+
+    /// Minimum percentages for an A, B, C, and D used by `--grade`
+    const DEFAULT_GRADE_CUTOFFS: [f64; 4] = [90.0, 80.0, 70.0, 60.0];
+
+    /// Overview printed for `--help` when no command is given, and when arguments can't be parsed
+    pub const USAGE: &str = "Usage: term_prep_plus [COMMAND] [OPTIONS]
+
+Commands:
+    study     Study an exam (default)
+    review    Review the notes taken during previous sessions
+    reports   List the questions that were reported as broken
+    check     Validate exam files for authoring mistakes
+    stats     Display statistics from previous study sessions
+    author    Interactively create or extend an exam file
+
+Every command accepts '--theme <dark|light|high-contrast|mono>' to choose the color palette.
+Run 'term_prep_plus <COMMAND> --help' for the options of each command.";
+
+    /// Help printed for `study --help`
+    const STUDY_HELP: &str = "Usage: term_prep_plus [study] [OPTIONS]
+
+Options:
+    --file <path>                       Study the given exam file instead of selecting one
+    --list <dir>                        List the exams in a directory (with names and sizes)
+    --url <https://...>                 Download and study an exam over HTTPS
+    --question <N>                      Only display question N (ordered by ID) of the --file
+    --answers <path>                    Grade a JSON file of answers to the --file's questions
+    --collect <path>                    Save answers to a response file without grading them
+    --key                               Print the --file's answer key instead of studying it
+    -d, --default                       Search the assets directory without asking
+    --random                            Study a randomly picked exam from the directory
+    --reveal <all|primary>              Which accepted answers to show after a wrong answer
+    --reveal-format <template>          How to reveal answers, e.g., 'Answer: {answers}'
+    --count-range <MIN> <MAX>           Study a random number of questions between MIN and MAX
+    --min-count <N>                     Refuse to study fewer than N questions
+    --allow-short                       Only warn when studying fewer than the --min-count
+    --difficulty <easy|medium|hard>     Only study questions with the given difficulty
+    --section <name>                    Only study questions of the given section
+    --ref <text>                        Only study questions with a reference containing text
+    --simulate                          Run a timed mock exam instead of a study session
+    --endless                           Keep reshuffling and asking questions until 'quit'
+    --learn-then-test                   Learn with feedback, then take a scored test without feedback
+    --rapid-fire <SECONDS>              Answer as many questions as possible in SECONDS
+    --recall                            Type the answer to every question instead of picking it
+    --sorted                            Display choices alphabetically instead of shuffled
+    --columns                           Display short choices side by side in columns
+    --arrow-keys                        Select multiple choice answers with the arrow keys
+    --compact                           Print feedback without labels, blank lines, or pauses
+    --hide-score                        Don't display scores (they're still saved to the history)
+    --transcript                        Save the session's output to a timestamped text file
+    --no-logo                           Don't print the logo at startup
+    --bell                              Ring the terminal bell after each answer
+    --correct-delay <MS>                Pause MS milliseconds after a correct answer's feedback
+    --incorrect-delay <MS>              Pause MS milliseconds after an incorrect answer's feedback
+    --praise <phrase>                   Say this after a correct answer (repeat to pick at random)
+    --consolation <phrase>              Say this after an incorrect answer (repeat to pick at random)
+    --lives <N>                         End the session after N incorrect answers
+    --goal <N>                          Track a daily goal of N answered questions
+    --bookmarked                        Only study bookmarked questions
+    --show-select-count                 Show how many choices multiple select questions expect
+    --confirm-ue                        Enter user entry answers twice to confirm them
+    --attempts <N>                      Try user entry questions N times before the answer is shown
+    --attempt-hints                     Show a growing hint after each wrong --attempts try
+    --teach-back                        Explain each answer before seeing the explanation
+    --grade                             Show a letter grade (A: 90%, B: 80%, C: 70%, D: 60%)
+    --grade-cutoffs <A,B,C,D>           Show a letter grade using the given minimum percentages
+    --penalty <POINTS>                  Deduct POINTS (e.g., 0.25) per incorrect answer
+    --hint-penalty <POINTS>             Deduct POINTS per hint revealed at a user entry question
+    --allow-negative                    Let the adjusted score drop below zero
+    --strategy <random|spaced|grouped>  Pick questions at random, least recently seen first, or by group
+    --warmup <N>                        Start with the N easiest questions, easiest first
+    --new-only                          Only study questions that have never been answered
+    --filter                            Combine filters in an interactive menu before studying
+    --skip-mastered                     Skip questions answered correctly several times in a row
+    --mastery-streak <N>                Correct answers in a row that master a question (default 3)
+    --set <name>                        Study a saved practice set in its saved order
+    --flag-misses <N>                   Bookmark questions missed more than N times (default 2)
+    --flag-slow <SECONDS>               Bookmark questions that take longer to answer (default 60)
+    -h, --help                          Print this help message";
+
+    /// Help printed for `review --help`
+    const REVIEW_HELP: &str = "Usage: term_prep_plus review
+
+Prints the notes taken with the 'note' command during previous sessions.
+
+Options:
+    -h, --help    Print this help message";
+
+    /// Help printed for `reports --help`
+    const REPORTS_HELP: &str = "Usage: term_prep_plus reports
+
+Lists the questions that were reported with the 'broken' command during previous sessions.
+
+Options:
+    -h, --help    Print this help message";
+
+    /// Help printed for `check --help`
+    const CHECK_HELP: &str = "Usage: term_prep_plus check [--file <path>]...
+
+Validates exam files for authoring mistakes; every exam file in the assets directory is checked
+when no files are given. Exits with a non-zero status if any problems are found.
+
+Options:
+    --file <path>    An exam file to check; may be repeated
+    -h, --help       Print this help message";
+
+    /// Help printed for `stats --help`
+    const STATS_HELP: &str = "Usage: term_prep_plus stats [--mode <practice|simulate|rapid-fire|learn>] [--decay <0-1>] [--compare]
+
+Displays statistics for each exam from the history of previous study sessions, broken down by
+session mode, along with a recent accuracy estimate that weighs newer sessions more.
+
+Options:
+    --mode <practice|simulate|rapid-fire|learn>    Only include sessions of the given mode
+    --decay <0-1>                                  Weight of each newer session in the recent estimate (default 0.3)
+    --compare                                      Compare the last two sessions of the most recently studied exam
+    -h, --help                                     Print this help message";
+
+    /// Help printed for `author --help`
+    const AUTHOR_HELP: &str = "Usage: term_prep_plus author --file <path>
+
+Interactively adds questions to the given exam file, creating it if it doesn't exist.
+
+Options:
+    --file <path>    The exam file to create or extend
+    -h, --help       Print this help message";
+
+    /// Returns the help message for the given command.
+    pub fn help(command: Command) -> &'static str {
+        match command {
+            Command::Study => STUDY_HELP,
+            Command::Review => REVIEW_HELP,
+            Command::Reports => REPORTS_HELP,
+            Command::Check => CHECK_HELP,
+            Command::Stats => STATS_HELP,
+            Command::Author => AUTHOR_HELP,
+        }
+    }
+
+    impl Settings {
+        /// Parses the command-line arguments (excluding the program name) into `Settings`. The
+        /// first argument may name a command; `study` is assumed when it doesn't.
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Settings, String>` - the parsed settings, or a message describing the first
+        ///   argument that couldn't be understood.
+        pub fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+            let mut settings = Settings::default();
+            let mut args = args.peekable();
+            if let Some(first) = args.next_if(|arg| !arg.starts_with('-')) {
+                settings.command = match first.as_str() {
+                    "study" => Command::Study,
+                    "review" => Command::Review,
+                    "reports" => Command::Reports,
+                    "check" => Command::Check,
+                    "stats" => Command::Stats,
+                    "author" => Command::Author,
+                    unknown => return Err(format!("Unrecognized command '{}'", unknown)),
+                };
+            }
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "-h" | "--help" => settings.help = true,
+                    "--file" => {
+                        settings.files.push(PathBuf::from(args.next().ok_or("--file requires a path".to_string())?));
+                    },
+                    "--list" => {
+                        settings.list = Some(PathBuf::from(args.next().ok_or("--list requires a directory".to_string())?));
+                    },
+                    "--url" => {
+                        settings.url = Some(args.next().ok_or("--url requires a value".to_string())?);
+                    },
+                    // Kept for compatibility with the flag that predates the `review` command
+                    "--notes" => settings.command = Command::Review,
+                    "--reports" => settings.command = Command::Reports,
+                    "--compare" => {
+                        settings.command = Command::Stats;
+                        settings.compare = true;
+                    },
+                    "-d" | "--default" => settings.use_default_dir = true,
+                    "--random" => settings.random = true,
+                    "--simulate" => settings.simulate = true,
+                    "--endless" => settings.endless = true,
+                    "--learn-then-test" => settings.learn_then_test = true,
+                    "--recall" => settings.recall = true,
+                    "--rapid-fire" => {
+                        settings.rapid_fire = match args.next().map(|seconds| seconds.parse::<u64>()) {
+                            Some(Ok(seconds)) if seconds > 0 => Some(seconds),
+                            _ => return Err("--rapid-fire requires a positive number of seconds".to_string()),
+                        };
+                    },
+                    "--bell" => settings.bell = true,
+                    "--transcript" => settings.transcript = true,
+                    "--no-logo" => settings.no_logo = true,
+                    "--hide-score" => settings.hide_score = true,
+                    "--compact" => settings.compact = true,
+                    "--sorted" => settings.sorted = true,
+                    "--columns" => settings.columns = true,
+                    "--arrow-keys" => settings.arrow_keys = true,
+                    "--lives" => {
+                        settings.lives = match args.next().map(|lives| lives.parse::<usize>()) {
+                            Some(Ok(lives)) if lives > 0 => Some(lives),
+                            _ => return Err("--lives requires a positive number".to_string()),
+                        };
+                    },
+                    "--correct-delay" => {
+                        settings.correct_delay = match args.next().map(|delay| delay.parse::<u64>()) {
+                            Some(Ok(delay)) => Some(delay),
+                            _ => return Err("--correct-delay requires a number of milliseconds".to_string()),
+                        };
+                    },
+                    "--incorrect-delay" => {
+                        settings.incorrect_delay = match args.next().map(|delay| delay.parse::<u64>()) {
+                            Some(Ok(delay)) => Some(delay),
+                            _ => return Err("--incorrect-delay requires a number of milliseconds".to_string()),
+                        };
+                    },
+                    "--praise" => {
+                        settings.praise.push(args.next().ok_or("--praise requires a phrase".to_string())?);
+                    },
+                    "--consolation" => {
+                        settings.consolation.push(args.next().ok_or("--consolation requires a phrase".to_string())?);
+                    },
+                    "--goal" => {
+                        settings.daily_goal = match args.next().map(|goal| goal.parse::<usize>()) {
+                            Some(Ok(goal)) if goal > 0 => Some(goal),
+                            _ => return Err("--goal requires a positive number".to_string()),
+                        };
+                    },
+                    "--ref" => {
+                        settings.reference = Some(args.next().ok_or("--ref requires a value".to_string())?);
+                    },
+                    "--section" => {
+                        settings.section = Some(args.next().ok_or("--section requires a name".to_string())?);
+                    },
+                    "--count-range" => {
+                        let mut count = || args.next().and_then(|count| count.parse::<usize>().ok());
+                        settings.count_range = match (count(), count()) {
+                            (Some(min_count), Some(max_count)) if min_count > 0 && min_count <= max_count => Some((min_count, max_count)),
+                            _ => return Err("--count-range requires two positive numbers, MIN and MAX, with MIN <= MAX".to_string()),
+                        };
+                    },
+                    "--min-count" => {
+                        settings.min_count = match args.next().map(|count| count.parse::<usize>()) {
+                            Some(Ok(count)) if count > 0 => Some(count),
+                            _ => return Err("--min-count requires a positive number".to_string()),
+                        };
+                    },
+                    "--allow-short" => settings.allow_short = true,
+                    "--warmup" => {
+                        settings.warmup = match args.next().map(|count| count.parse::<usize>()) {
+                            Some(Ok(count)) if count > 0 => Some(count),
+                            _ => return Err("--warmup requires a positive number".to_string()),
+                        };
+                    },
+                    "--question" => {
+                        settings.question = match args.next().map(|number| number.parse::<usize>()) {
+                            Some(Ok(number)) if number > 0 => Some(number),
+                            _ => return Err("--question requires a positive number".to_string()),
+                        };
+                    },
+                    "--collect" => {
+                        settings.collect = Some(PathBuf::from(args.next().ok_or("--collect requires a path".to_string())?));
+                    },
+                    "--key" => {
+                        settings.key = true;
+                    },
+                    "--answers" => {
+                        settings.answers = Some(PathBuf::from(args.next().ok_or("--answers requires a path".to_string())?));
+                    },
+                    "--bookmarked" => settings.bookmarked = true,
+                    "--new-only" => settings.new_only = true,
+                    "--filter" => settings.filter_builder = true,
+                    "--skip-mastered" => settings.skip_mastered = true,
+                    "--mastery-streak" => {
+                        settings.mastery_streak = match args.next().map(|streak| streak.parse::<usize>()) {
+                            Some(Ok(streak)) if streak > 0 => Some(streak),
+                            _ => return Err("--mastery-streak requires a positive number".to_string()),
+                        };
+                    },
+                    "--show-select-count" => settings.show_select_count = true,
+                    "--confirm-ue" => settings.confirm_ue = true,
+                    "--attempts" => {
+                        settings.ue_attempts = match args.next().map(|attempts| attempts.parse::<usize>()) {
+                            Some(Ok(attempts)) if attempts > 0 => Some(attempts),
+                            _ => return Err("--attempts requires a positive number".to_string()),
+                        };
+                    },
+                    "--attempt-hints" => settings.attempt_hints = true,
+                    "--teach-back" => settings.teach_back = true,
+                    "--penalty" => {
+                        settings.penalty = match args.next().map(|points| points.parse::<f64>()) {
+                            Some(Ok(points)) if points.is_finite() && points >= 0.0 => Some(points),
+                            _ => return Err("--penalty requires a non-negative number of points".to_string()),
+                        };
+                    },
+                    "--hint-penalty" => {
+                        settings.hint_penalty = match args.next().map(|points| points.parse::<f64>()) {
+                            Some(Ok(points)) if points.is_finite() && points >= 0.0 => Some(points),
+                            _ => return Err("--hint-penalty requires a non-negative number of points".to_string()),
+                        };
+                    },
+                    "--allow-negative" => settings.allow_negative = true,
+                    "--grade" => settings.grade_cutoffs = Some(DEFAULT_GRADE_CUTOFFS),
+                    "--grade-cutoffs" => {
+                        let cutoffs: Option<Vec<f64>> = args.next().and_then(|cutoffs| {
+                            cutoffs.split(',').map(|cutoff| cutoff.trim().parse::<f64>().ok()).collect()
+                        });
+                        settings.grade_cutoffs = match cutoffs.as_deref() {
+                            Some(&[a, b, c, d]) if a <= 100.0 && a >= b && b >= c && c >= d && d >= 0.0 => Some([a, b, c, d]),
+                            _ => return Err("--grade-cutoffs requires four descending percentages (e.g., '90,80,70,60')".to_string()),
+                        };
+                    },
+                    "--set" => {
+                        settings.practice_set = Some(args.next().ok_or("--set requires a name".to_string())?);
+                    },
+                    "--flag-misses" => {
+                        settings.flag_misses = match args.next().map(|misses| misses.parse::<usize>()) {
+                            Some(Ok(misses)) => Some(misses),
+                            _ => return Err("--flag-misses requires a number".to_string()),
+                        };
+                    },
+                    "--flag-slow" => {
+                        settings.flag_seconds = match args.next().map(|seconds| seconds.parse::<u64>()) {
+                            Some(Ok(seconds)) if seconds > 0 => Some(seconds),
+                            _ => return Err("--flag-slow requires a positive number of seconds".to_string()),
+                        };
+                    },
+                    "--difficulty" => {
+                        settings.difficulty = match args.next().as_deref() {
+                            Some("easy") => Some(Difficulty::Easy),
+                            Some("medium") => Some(Difficulty::Medium),
+                            Some("hard") => Some(Difficulty::Hard),
+                            _ => return Err("--difficulty requires 'easy', 'medium', or 'hard'".to_string()),
+                        };
+                    },
+                    "--decay" => {
+                        settings.decay = match args.next().map(|decay| decay.parse::<f64>()) {
+                            Some(Ok(decay)) if decay > 0.0 && decay <= 1.0 => Some(decay),
+                            _ => return Err("--decay requires a number greater than 0 and at most 1".to_string()),
+                        };
+                    },
+                    "--mode" => {
+                        settings.mode = match args.next().as_deref() {
+                            Some("practice") => Some(SessionMode::Practice),
+                            Some("simulate") => Some(SessionMode::Simulate),
+                            Some("rapid-fire") => Some(SessionMode::RapidFire),
+                            Some("learn") => Some(SessionMode::Learn),
+                            _ => return Err("--mode requires 'practice', 'simulate', 'rapid-fire', or 'learn'".to_string()),
+                        };
+                    },
+                    "--theme" => {
+                        settings.theme = match args.next().as_deref() {
+                            Some("dark") => Theme::Dark,
+                            Some("light") => Theme::Light,
+                            Some("high-contrast") => Theme::HighContrast,
+                            Some("mono") => Theme::Mono,
+                            _ => return Err("--theme requires 'dark', 'light', 'high-contrast', or 'mono'".to_string()),
+                        };
+                    },
+                    "--strategy" => {
+                        settings.strategy = match args.next().as_deref() {
+                            Some("random") => Strategy::Random,
+                            Some("spaced") => Strategy::Spaced,
+                            Some("grouped") => Strategy::Grouped,
+                            _ => return Err("--strategy requires 'random', 'spaced', or 'grouped'".to_string()),
+                        };
+                    },
+                    "--reveal-format" => {
+                        settings.reveal_template = match args.next() {
+                            Some(template) if template.contains("{answers}") => Some(template),
+                            _ => return Err("--reveal-format requires a template containing '{answers}'".to_string()),
+                        };
+                    },
+                    "--reveal" => {
+                        settings.reveal = match args.next().as_deref() {
+                            Some("all") => Reveal::All,
+                            Some("primary") => Reveal::Primary,
+                            _ => return Err("--reveal requires either 'all' or 'primary'".to_string()),
+                        };
+                    },
+                    unknown => return Err(format!("Unrecognized argument '{}'", unknown)),
+                }
+            }
+            Ok(settings)
         }
     }
 }
 
 fn main() {
-    println!("{}", LOGO);
-    if let Some(exam) = Exam::new() {
-        exam.study();
-    } else {
+    let settings = match Settings::from_args(env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, settings::USAGE);
+            exit(2);
+        },
+    };
+    if settings.help {
+        println!("{}", settings::help(settings.command));
+        return;
+    }
+    exam::set_theme(settings.theme);
+    if let Some(dir) = &settings.list {
+        if !Exam::list(dir) {
+            exit(1);
+        }
+        return;
+    }
+    match settings.command {
+        Command::Study => study(&settings),
+        Command::Review => {
+            if let Err(e) = notes::display() {
+                eprintln!("Unable to read notes: {}", e);
+                exit(1);
+            }
+        },
+        Command::Reports => {
+            if let Err(e) = reports::display() {
+                eprintln!("Unable to read reports: {}", e);
+                exit(1);
+            }
+        },
+        Command::Check => {
+            if !Exam::check(&settings.files) {
+                exit(1);
+            }
+        },
+        Command::Stats => {
+            let displayed = if settings.compare {
+                history::display_comparison(settings.mode)
+            } else {
+                history::display_stats(settings.mode, settings.decay)
+            };
+            if let Err(e) = displayed {
+                eprintln!("Unable to read study history: {}", e);
+                exit(1);
+            }
+        },
+        Command::Author => match settings.files.as_slice() {
+            [path] => Exam::author(path),
+            _ => {
+                eprintln!("The author command requires exactly one --file\n\n{}", settings::help(Command::Author));
+                exit(2);
+            },
+        },
+    }
+}
+
+/// Runs the `study` command; the exam is acquired from a file, a URL, or the interactive
+/// directory flow (in that order of preference).
+fn study(settings: &Settings) {
+    // The logo's box-drawing characters only make sense in an interactive terminal, and one
+    // that is too narrow for them gets the one-line banner instead
+    if !settings.no_logo && exam::stdout_is_terminal() {
+        let logo_width = LOGO.lines().map(|line| line.chars().count()).max().unwrap_or_default();
+        if exam::terminal_width() < logo_width {
+            println!("\n{}\n", BANNER);
+        } else {
+            println!("{}", LOGO);
+        }
+    }
+    if (settings.question.is_some() || settings.answers.is_some() || settings.key) && settings.files.len() != 1 {
+        eprintln!("--question, --answers, and --key require exactly one --file\n\n{}", settings::help(Command::Study));
+        exit(2);
+    }
+    // The exam files that can be continued with after the first; every `--file` after the first,
+    // or the files listed after the selected one in the interactive flow
+    let selected = match (settings.files.split_first(), &settings.url) {
+        (Some((path, next_paths)), _) => Exam::from_file(path).map(|exam| (exam, next_paths.to_vec())),
+        (None, Some(url)) => Exam::from_url(url).map(|exam| (exam, Vec::new())),
+        // Running without any arguments at all allows the quick quiz
+        (None, None) => Exam::new(settings.use_default_dir, settings.random, env::args().len() == 1),
+    };
+    let Some((exam, next_paths)) = selected else {
         eprintln!("Unable to study today...");
         exit(1);
+    };
+    if settings.transcript {
+        if let Err(e) = exam::start_transcript() {
+            eprintln!("Unable to create the transcript file: {}", e);
+            exit(1);
+        }
+    }
+    if settings.key {
+        exam.print_key();
+    } else if let Some(path) = &settings.answers {
+        if !exam.grade_responses(path) {
+            exit(1);
+        }
+    } else if let Some(path) = &settings.collect {
+        if !exam.collect_responses(path, settings) {
+            exit(1);
+        }
+    } else if let Some(number) = settings.question {
+        if !exam.preview(number, settings) {
+            exit(1);
+        }
+    } else if settings.simulate {
+        exam.simulate(settings);
+    } else if let Some(seconds) = settings.rapid_fire {
+        exam.rapid_fire(settings, seconds);
+    } else {
+        study_in_sequence(exam, next_paths, settings);
+    }
+    if let Some(path) = exam::transcript_path() {
+        println!("Transcript saved to {}", path.display());
+    }
+}
+
+/// Studies the given exam, then each of the next exams for as long as the user chooses to
+/// continue; cumulative totals are displayed when more than one exam was studied.
+fn study_in_sequence(first: Exam, next_paths: Vec<PathBuf>, settings: &Settings) {
+    let mut next_paths = next_paths.into_iter();
+    let mut exam = first;
+    let (mut num_exams, mut num_correct, mut num_answered) = (0, 0, 0);
+    loop {
+        let outcome = exam.study(settings, next_paths.len() > 0);
+        num_exams += 1;
+        num_correct += outcome.correct;
+        num_answered += outcome.answered;
+        if !outcome.next_exam {
+            break;
+        }
+        // Exams that can't be loaded are skipped
+        match next_paths.by_ref().find_map(|path| Exam::from_file(&path)) {
+            Some(next) => exam = next,
+            None => break,
+        }
+    }
+    if num_exams > 1 && !settings.hide_score {
+        println!("\nAcross {} exams, you got {}/{} questions correct.", num_exams, num_correct, num_answered);
     }
 }